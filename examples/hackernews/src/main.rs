@@ -24,7 +24,7 @@ mod ssr {
             http, web, HttpRequest, HttpResponse, Result,
         };
         use futures::StreamExt;
-        use tachy_reaccy::{context::provide_context, Owner, Root};
+        use tachy_reaccy::{context::provide_context, Root};
         use tachy_route::{location::RequestUrl, PathSegment, RouteList};
         use tachys::tachydom::{renderer::dom::Dom, view::RenderHtml};
 
@@ -129,23 +129,8 @@ mod ssr {
                                         let app = app_fn();
 
                                         // convert app to appropriate response type
-                                        let app_stream =
-                                            app.to_html_stream_out_of_order();
-                                        let shared_context =
-                                            Owner::shared_context().unwrap();
-                                        // TODO nonce
-                                        let shared_context = shared_context
-                                            .pending_data()
-                                            .unwrap()
-                                            .map(|chunk| {
-                                                format!(
-                                                    "<script>{chunk}</script>"
-                                                )
-                                            });
-                                        futures::stream::select(
-                                            app_stream,
-                                            shared_context,
-                                        )
+                                        app.to_html_stream_out_of_order()
+                                            .with_resource_data()
                                     });
                                 std::mem::forget(owner); // TOOD close leak
 
@@ -4,7 +4,7 @@ async fn main() -> std::io::Result<()> {
     use actix_files::Files;
     use actix_web::*;
     use futures::StreamExt;
-    use tachy_reaccy::{Owner, Root};
+    use tachy_reaccy::Root;
     use tachydom::{
         renderer::dom::Dom,
         view::{Position, PositionState, Render, RenderHtml},
@@ -22,21 +22,8 @@ async fn main() -> std::io::Result<()> {
                     let Root(owner, stream) = Root::global_ssr(move || {
 
                                 let app = hydration_ex::app::my_app();
-                                let app_stream =
-                                    app.to_html_stream_out_of_order();
-                                let shared_context =
-                                    Owner::shared_context().unwrap();
-                                // TODO nonce
-                                let shared_context = shared_context
-                                    .pending_data()
-                                    .unwrap()
-                                    .map(|chunk| {
-                                        format!("<script>{chunk}</script>")
-                                    });
-                                futures::stream::select(
-                                    app_stream,
-                                    shared_context,
-                                )
+                                app.to_html_stream_out_of_order()
+                                    .with_resource_data()
                     });
                                             HttpResponse::Ok()
                             .content_type(http::header::ContentType::html())
@@ -9,14 +9,16 @@ use proc_macro2::{Ident, Span, TokenStream};
 use proc_macro_error::abort;
 use quote::{format_ident, quote, quote_spanned, ToTokens, TokenStreamExt};
 use syn::{
-    parse::Parse, parse_quote, spanned::Spanned,
-    AngleBracketedGenericArguments, Attribute, FnArg, GenericArgument, Item,
-    ItemFn, LitStr, Meta, Pat, PatIdent, Path, PathArguments, ReturnType,
-    Signature, Stmt, Type, TypePath, Visibility,
+    parse::Parse, parse_quote, punctuated::Punctuated, spanned::Spanned,
+    AngleBracketedGenericArguments, Attribute, Expr, ExprLit, Field, FnArg,
+    GenericArgument, Item, ItemFn, Lit, LitStr, Meta, MetaNameValue, Pat,
+    PatIdent, Path, PathArguments, ReturnType, Signature, Stmt, Token, Type,
+    TypeParamBound, TypePath, Visibility,
 };
 
 pub struct Model {
     is_island: bool,
+    hydrate: Option<TokenStream>,
     docs: Docs,
     vis: Visibility,
     name: Ident,
@@ -58,6 +60,7 @@ impl Parse for Model {
 
         Ok(Self {
             is_island: false,
+            hydrate: None,
             docs,
             vis: item.vis.clone(),
             name: convert_from_snake_case(&item.sig.ident),
@@ -97,6 +100,7 @@ impl ToTokens for Model {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let Self {
             is_island,
+            hydrate,
             docs,
             vis,
             name,
@@ -144,12 +148,13 @@ impl ToTokens for Model {
             && ((is_island_with_children && props.len() > 1)
                 || (!is_island_with_children && !props.is_empty()));
 
-        let prop_builder_fields =
-            prop_builder_fields(vis, props, is_island_with_other_props);
+        let prop_builder_fields = prop_builder_fields(vis, props);
         let props_serializer = if is_island_with_other_props {
             let fields = prop_serializer_fields(vis, props);
             quote! {
-                #[derive(::leptos::serde::Deserialize)]
+                #[derive(::tachys::serde::Serialize, ::tachys::serde::Deserialize)]
+                #[serde(crate = "::tachys::serde")]
+                #[allow(non_snake_case)]
                 #vis struct #props_serialized_name {
                     #fields
                 }
@@ -204,16 +209,30 @@ impl ToTokens for Model {
         let hydrate_fn_name =
             Ident::new(&format!("_island_{}", component_id), name.span());
 
+        // the props other than children are moved into the serializable
+        // struct and back out again, so they don't need to be `Clone`; they
+        // are not serialized again while the island is hydrating
         let island_serialize_props = if is_island_with_other_props {
+            let prop_names = serialized_prop_names(props);
             quote! {
-                let _leptos_ser_props = ::leptos::serde_json::to_string(&props).expect("couldn't serialize island props");
+                let _island_props = #props_serialized_name { #prop_names };
+                let _island_props_data = ::tachys::tachydom::html::islands::should_serialize_props().then(|| {
+                    <#props_serialized_name as ::tachys::tachy_reaccy::serialization::SerializableData<
+                        ::tachys::tachy_reaccy::serialization::SerdeJson,
+                    >>::ser(&_island_props)
+                        .expect("couldn't serialize island props")
+                });
+                let #props_serialized_name { #prop_names } = _island_props;
             }
         } else {
             quote! {}
         };
         let island_serialized_props = if is_island_with_other_props {
             quote! {
-                .attr("data-props", _leptos_ser_props)
+                let island = match _island_props_data {
+                    Some(props) => island.with_props(props),
+                    None => island,
+                };
             }
         } else {
             quote! {}
@@ -244,13 +263,19 @@ impl ToTokens for Model {
 
         // add island wrapper if island
         let component = if *is_island {
+            let hydrate = hydrate.as_ref().map(|strategy| {
+                quote! {
+                    .with_hydration(::tachys::tachydom::html::islands::HydrationStrategy::#strategy)
+                }
+            });
             quote! {
                 {
-                    ::tachys::tachydom::html::islands::Island::new(
+                    let island = ::tachys::tachydom::html::islands::Island::new(
                         #component_id,
                         #component
-                    )
-                        // #island_serialized_props
+                    );
+                    #island_serialized_props
+                    island #hydrate
                 }
             }
         } else {
@@ -277,47 +302,14 @@ impl ToTokens for Model {
                 quote! {}
             };
             quote! {
-                #island_serialize_props
                 let #props_name {
                     #prop_names
                 } = props;
+                #island_serialize_props
                 #wrapped_children
             }
         };
 
-        let count = props
-            .iter()
-            .filter(
-                |Prop {
-                     prop_opts: PropOpt { attrs, .. },
-                     ..
-                 }| *attrs,
-            )
-            .count();
-
-        let dyn_attrs_props = props
-            .iter()
-            .filter(
-                |Prop {
-                     prop_opts: PropOpt { attrs, .. },
-                     ..
-                 }| *attrs,
-            )
-            .enumerate()
-            .map(|(idx, Prop { name, .. })| {
-                let ident = &name.ident;
-                if idx < count - 1 {
-                    quote! {
-                        self.#ident = v.clone().into();
-                    }
-                } else {
-                    quote! {
-                        self.#ident = v.into();
-                    }
-                }
-            })
-            .collect::<TokenStream>();
-
         let body = quote! {
             #destructure_props
             #tracing_span_expr
@@ -330,17 +322,7 @@ impl ToTokens for Model {
             {
                 let (destructure, prop_builders) = if is_island_with_other_props
                 {
-                    let prop_names = props
-                        .iter()
-                        .filter_map(|prop| {
-                            if prop.name.ident == "children" {
-                                None
-                            } else {
-                                let name = &prop.name.ident;
-                                Some(quote! { #name, })
-                            }
-                        })
-                        .collect::<TokenStream>();
+                    let prop_names = serialized_prop_names(props);
                     let destructure = quote! {
                         let #props_serialized_name {
                             #prop_names
@@ -363,22 +345,17 @@ impl ToTokens for Model {
                 } else {
                     (quote! {}, quote! {})
                 };
+                // the children were rendered on the server, and are left as
+                // they are, so they don't need to be rendered here; any
+                // islands inside them find this island's owner when they
+                // are hydrated
                 let children = if is_island_with_children {
                     quote! {
                         .children({Box::new(|| {
                             use tachys::tachydom::view::any_view::IntoAny;
                             ::tachys::tachydom::html::islands::IslandChildren::new(
-                                // TODO owner restoration for context
                                 ()
                             ).into_any()})})
-                        //.children(children)
-                        /*.children(Box::new(|| {
-                            use tachys::tachydom::view::any_view::IntoAny;
-                            ::tachys::tachydom::html::islands::IslandChildren::new(
-                                // TODO owner restoration for context
-                                ()
-                            ).into_any()
-                        }))*/
                     }
                 } else {
                     quote! {}
@@ -394,46 +371,50 @@ impl ToTokens for Model {
             } else {
                 quote! {}
             };
-            let deserialize_island_props = quote! {}; /*if is_island_with_other_props {
-                                                          quote! {
-                                                              let props = el.dataset().get("props") // TODO ::leptos::wasm_bindgen::intern("props"))
-                                                                  .and_then(|data| ::leptos::serde_json::from_str::<#props_serialized_name>(&data).ok())
-                                                                  .expect("could not deserialize props");
-                                                          }
-                                                      } else {
-                                                          quote! {}
-                                                      };*/
-            // TODO
+            let deserialize_island_props = if is_island_with_other_props {
+                quote! {
+                    let props = el
+                        .dataset()
+                        .get(::tachys::tachydom::wasm_bindgen::intern("props"))
+                        .and_then(|data| {
+                            <#props_serialized_name as ::tachys::tachy_reaccy::serialization::SerializableData<
+                                ::tachys::tachy_reaccy::serialization::SerdeJson,
+                            >>::de(&data)
+                            .ok()
+                        })
+                        .expect("could not deserialize island props");
+                }
+            } else {
+                quote! {}
+            };
 
             quote! {
                 #[::tachys::tachydom::wasm_bindgen::prelude::wasm_bindgen]
                 #[allow(non_snake_case)]
                 pub fn #hydrate_fn_name(el: ::tachys::tachydom::web_sys::HtmlElement) {
                     #deserialize_island_props
-                    ::tachys::tachydom::web_sys::console::log_2(&tachys::tachydom::wasm_bindgen::JsValue::from_str("island is"), &el);
-                    let island = #name(#island_props);
-                    let state = island.hydrate_from_position::<true>(&el, ::tachys::tachydom::view::Position::Current);
+                    let island = ::tachys::tachydom::html::islands::with_island_owner::<
+                        ::tachys::tachydom::renderer::dom::Dom,
+                        _,
+                    >(&el, || {
+                        let island = #name(#island_props);
+                        island.hydrate_from_position::<true>(&el, ::tachys::tachydom::view::Position::Current)
+                    });
                     // TODO better cleanup
-                    std::mem::forget(state);
+                    std::mem::forget(island);
                 }
             }
         } else {
             quote! {}
         };
 
-        let props_derive_serialize = if is_island_with_other_props {
-            quote! { , ::leptos::serde::Serialize }
-        } else {
-            quote! {}
-        };
-
         let output = quote! {
             #[doc = #builder_name_doc]
             #[doc = ""]
             #docs
             #[doc = ""]
             #component_fn_prop_docs
-            #[derive(::tachys::typed_builder_macro::TypedBuilder #props_derive_serialize)]
+            #[derive(::tachys::typed_builder_macro::TypedBuilder)]
             //#[builder(doc)]
             #[builder(crate_module_path=::tachys::typed_builder)]
             #[allow(non_snake_case)]
@@ -454,14 +435,6 @@ impl ToTokens for Model {
                 }
             }
 
-            // TODO restore dyn attrs
-            /*impl #impl_generics ::leptos::DynAttrs for #props_name #generics #where_clause {
-                fn dyn_attrs(mut self, v: Vec<(&'static str, ::leptos::Attribute)>) -> Self {
-                    #dyn_attrs_props
-                    self
-                }
-            } */
-
             #docs
             #[doc = ""]
             #component_fn_prop_docs
@@ -486,10 +459,119 @@ impl Model {
     pub fn is_island(mut self, is_island: bool) -> Self {
         self.is_island = is_island;
 
+        // an island renders inside its own wrapper element, so attributes
+        // from the call site have nowhere to go
+        if is_island {
+            if let Some(bound) = add_any_attr_bound(&self.ret) {
+                abort!(
+                    bound,
+                    "islands can't accept attributes forwarded from where \
+                     they are used";
+                    help = "remove `AddAnyAttr` from the return type, and \
+                            set attributes on the elements inside the island"
+                );
+            }
+        }
+
+        self
+    }
+
+    pub fn with_island_args(mut self, args: IslandArgs) -> Self {
+        self.hydrate = args.hydrate;
+
         self
     }
 }
 
+/// The arguments to `#[island]`, like `#[island(hydrate = "visible")]`.
+pub struct IslandArgs {
+    hydrate: Option<TokenStream>,
+}
+
+impl Parse for IslandArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let args =
+            Punctuated::<MetaNameValue, Token![,]>::parse_terminated(input)?;
+
+        let mut hydrate = None;
+        let mut media = None;
+        for arg in args {
+            let value = match &arg.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(value),
+                    ..
+                }) => value.clone(),
+                other => {
+                    return Err(syn::Error::new(
+                        other.span(),
+                        "expected a string literal",
+                    ))
+                }
+            };
+            if arg.path.is_ident("hydrate") {
+                hydrate = Some(value);
+            } else if arg.path.is_ident("media") {
+                media = Some(value);
+            } else {
+                return Err(syn::Error::new(
+                    arg.path.span(),
+                    "unknown island argument; expected `hydrate` or `media`",
+                ));
+            }
+        }
+
+        let hydrate = match (hydrate, media) {
+            (None, None) => None,
+            (None, Some(media)) => {
+                return Err(syn::Error::new(
+                    media.span(),
+                    "`media` can only be used with `hydrate = \"media\"`",
+                ))
+            }
+            (Some(hydrate), media) => {
+                let strategy = match (hydrate.value().as_str(), media) {
+                    ("load", None) => quote! { Load },
+                    ("idle", None) => quote! { Idle },
+                    ("visible", None) => quote! { Visible },
+                    ("interaction", None) => quote! { Interaction },
+                    ("media", Some(query)) => quote! { Media(#query) },
+                    ("media", None) => {
+                        return Err(syn::Error::new(
+                            hydrate.span(),
+                            "`hydrate = \"media\"` needs a query, like \
+                             `media = \"(min-width: 800px)\"`",
+                        ))
+                    }
+                    (_, Some(media)) if is_hydration_strategy(&hydrate) => {
+                        return Err(syn::Error::new(
+                            media.span(),
+                            "`media` can only be used with `hydrate = \"media\"`",
+                        ))
+                    }
+                    _ => {
+                        return Err(syn::Error::new(
+                            hydrate.span(),
+                            "unknown hydration strategy; expected one of \
+                             \"load\", \"idle\", \"visible\", \
+                             \"interaction\" or \"media\"",
+                        ))
+                    }
+                };
+                Some(strategy)
+            }
+        };
+
+        Ok(Self { hydrate })
+    }
+}
+
+fn is_hydration_strategy(value: &LitStr) -> bool {
+    matches!(
+        value.value().as_str(),
+        "load" | "idle" | "visible" | "interaction" | "media"
+    )
+}
+
 /// A model that is more lenient in case of a syntax error in the function body,
 /// but does not actually implement the behavior of the real model. This is
 /// used to improve IDEs and rust-analyzer's auto-completion behavior in case
@@ -565,7 +647,7 @@ impl ToTokens for DummyModel {
     }
 }
 
-struct Prop {
+pub(crate) struct Prop {
     docs: Docs,
     prop_opts: PropOpt,
     name: PatIdent,
@@ -603,6 +685,34 @@ impl Prop {
             ty: *typed.ty,
         }
     }
+
+    pub(crate) fn from_field(field: Field) -> Self {
+        let prop_opts =
+            PropOpt::from_attributes(&field.attrs).unwrap_or_else(|e| {
+                abort!(e.span(), e.to_string());
+            });
+
+        let Some(ident) = field.ident else {
+            abort!(
+                field,
+                "only structs with named fields are allowed within the \
+                 `#[slot]` macro"
+            );
+        };
+
+        Self {
+            docs: Docs::new(&field.attrs),
+            prop_opts,
+            name: PatIdent {
+                attrs: Vec::new(),
+                by_ref: None,
+                mutability: None,
+                ident,
+                subpat: None,
+            },
+            ty: field.ty,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -848,10 +958,9 @@ impl ToTokens for TypedBuilderOpts {
     }
 }
 
-fn prop_builder_fields(
+pub(crate) fn prop_builder_fields(
     vis: &Visibility,
     props: &[Prop],
-    is_island_with_other_props: bool,
 ) -> TokenStream {
     props
         .iter()
@@ -874,12 +983,6 @@ fn prop_builder_fields(
             } else {
                 quote!()
             };
-            let skip_children_serde =
-                if is_island_with_other_props && name.ident == "children" {
-                    quote!(#[serde(skip)])
-                } else {
-                    quote!()
-                };
 
             let PatIdent { ident, by_ref, .. } = &name;
 
@@ -888,7 +991,6 @@ fn prop_builder_fields(
                 #builder_docs
                 #builder_attrs
                 #allow_missing_docs
-                #skip_children_serde
                 #vis #by_ref #ident: #ty,
             }
         })
@@ -937,7 +1039,19 @@ fn prop_names(props: &[Prop]) -> TokenStream {
         .collect()
 }
 
-fn generate_component_fn_prop_docs(props: &[Prop]) -> TokenStream {
+// the names of the props that are serialized for an island
+fn serialized_prop_names(props: &[Prop]) -> TokenStream {
+    props
+        .iter()
+        .filter(|prop| prop.name.ident != "children")
+        .map(|Prop { name, .. }| {
+            let ident = &name.ident;
+            quote! { #ident, }
+        })
+        .collect()
+}
+
+pub(crate) fn generate_component_fn_prop_docs(props: &[Prop]) -> TokenStream {
     let required_prop_docs = props
         .iter()
         .filter(|Prop { prop_opts, .. }| {
@@ -1115,6 +1229,24 @@ fn prop_to_doc(
     }
 }
 
+fn add_any_attr_bound(ret: &ReturnType) -> Option<&TypeParamBound> {
+    let ReturnType::Type(_, ty) = ret else {
+        return None;
+    };
+    let Type::ImplTrait(impl_trait) = &**ty else {
+        return None;
+    };
+    impl_trait.bounds.iter().find(|bound| {
+        matches!(
+            bound,
+            TypeParamBound::Trait(bound)
+                if bound.path.segments.last().is_some_and(
+                    |segment| segment.ident == "AddAnyAttr"
+                )
+        )
+    })
+}
+
 pub fn unmodified_fn_name_from_fn_name(ident: &Ident) -> Ident {
     Ident::new(&format!("__{ident}"), ident.span())
 }
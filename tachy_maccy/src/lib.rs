@@ -1,7 +1,8 @@
 mod component;
+mod slot;
 mod view;
 use crate::component::unmodified_fn_name_from_fn_name;
-use component::DummyModel;
+use component::{DummyModel, IslandArgs};
 use proc_macro::TokenStream;
 use proc_macro2::TokenTree;
 use proc_macro_error::abort;
@@ -59,27 +60,110 @@ pub fn view(tokens: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Defines a component, which can be used in the `view` macro like an element
+/// and takes its arguments as props.
+///
+/// Attributes, classes, event listeners and directives written where the
+/// component is used, like `<Button attr:id="go" on:click=...>`, are
+/// forwarded onto the element at the root of its view. This requires the
+/// component to promise that it has one, by returning
+/// `impl RenderHtml<Dom> + AddAnyAttr<Dom>`:
+///
+/// ```rust,ignore
+/// #[component]
+/// fn Button(label: &'static str) -> impl RenderHtml<Dom> + AddAnyAttr<Dom> {
+///     view! { <button class="btn">{label}</button> }
+/// }
+///
+/// view! { <Button label="Go" attr:id="go" class:big=true/> }
+/// ```
 #[proc_macro_error::proc_macro_error]
 #[proc_macro_attribute]
 pub fn component(
     _args: proc_macro::TokenStream,
     s: TokenStream,
 ) -> TokenStream {
-    component_macro(s, false)
+    component_macro(s, None)
 }
 
+/// Defines a component as an interactive island, which is rendered on the
+/// server and hydrated on its own in the browser.
+///
+/// By default, islands are hydrated as soon as the WASM module loads. Pass
+/// `hydrate = "idle"`, `"visible"`, `"interaction"` or `"media"` to delay
+/// that until it's needed, along with a `media` query for the last of those:
+/// `#[island(hydrate = "media", media = "(min-width: 800px)")]`.
 #[proc_macro_error::proc_macro_error]
 #[proc_macro_attribute]
-pub fn island(_args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
-    component_macro(s, true)
+pub fn island(args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
+    let args = match syn::parse::<IslandArgs>(args) {
+        Ok(args) => args,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    component_macro(s, Some(args))
+}
+
+/// Defines a slot: a named, typed region of content that a component can
+/// accept in addition to its `children`.
+///
+/// The fields of the struct are its props, which take the same `#[prop]`
+/// options as those of a component. A component accepts the slot with a prop
+/// of the slot's type (or `Vec` of it, to take several, or `Option` of it, to
+/// make it optional), and it is passed in the `view` macro as a child marked
+/// with `slot`:
+///
+/// ```rust,ignore
+/// #[slot]
+/// struct Header {
+///     children: Children,
+/// }
+///
+/// #[component]
+/// fn Card(header: Header, children: Children) -> impl RenderHtml<Dom> {
+///     view! {
+///         <section>
+///             <header>{(header.children)()}</header>
+///             {children()}
+///         </section>
+///     }
+/// }
+///
+/// view! {
+///     <Card>
+///         <Header slot>"Title"</Header>
+///         <p>"Body"</p>
+///     </Card>
+/// }
+/// ```
+///
+/// By default, the slot fills the prop with the slot's name in `snake_case`;
+/// use `<Header slot:title>` to fill a differently-named prop.
+#[proc_macro_error::proc_macro_error]
+#[proc_macro_attribute]
+pub fn slot(args: proc_macro::TokenStream, s: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        abort!(
+            proc_macro2::TokenStream::from(args),
+            "`#[slot]` does not take any arguments"
+        );
+    }
+
+    match syn::parse::<slot::Model>(s) {
+        Ok(model) => model.into_token_stream().into(),
+        Err(e) => e.to_compile_error().into(),
+    }
 }
 
-fn component_macro(s: TokenStream, island: bool) -> TokenStream {
+fn component_macro(s: TokenStream, island: Option<IslandArgs>) -> TokenStream {
     let mut dummy = syn::parse::<DummyModel>(s.clone());
     let parse_result = syn::parse::<component::Model>(s);
 
     if let (Ok(ref mut unexpanded), Ok(model)) = (&mut dummy, parse_result) {
-        let expanded = model.is_island(island).into_token_stream();
+        let model = match island {
+            Some(args) => model.is_island(true).with_island_args(args),
+            None => model,
+        };
+        let expanded = model.into_token_stream();
         if !matches!(unexpanded.vis, Visibility::Public(_)) {
             unexpanded.vis = Visibility::Public(Pub {
                 span: unexpanded.vis.span(),
@@ -0,0 +1,78 @@
+use crate::component::{
+    generate_component_fn_prop_docs, prop_builder_fields, Docs, Prop,
+};
+use proc_macro2::{Ident, TokenStream};
+use proc_macro_error::abort;
+use quote::{quote, ToTokens, TokenStreamExt};
+use syn::{parse::Parse, Fields, Generics, ItemStruct, Visibility};
+
+pub struct Model {
+    docs: Docs,
+    vis: Visibility,
+    name: Ident,
+    props: Vec<Prop>,
+    generics: Generics,
+}
+
+impl Parse for Model {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let item = ItemStruct::parse(input)?;
+
+        let props = match item.fields {
+            Fields::Named(fields) => {
+                fields.named.into_iter().map(Prop::from_field).collect()
+            }
+            Fields::Unit => Vec::new(),
+            Fields::Unnamed(fields) => abort!(
+                fields,
+                "only structs with named fields are allowed within the \
+                 `#[slot]` macro"
+            ),
+        };
+
+        Ok(Self {
+            docs: Docs::new(&item.attrs),
+            vis: item.vis,
+            name: item.ident,
+            props,
+            generics: item.generics,
+        })
+    }
+}
+
+impl ToTokens for Model {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Self {
+            docs,
+            vis,
+            name,
+            props,
+            generics,
+        } = self;
+
+        let (impl_generics, ty_generics, where_clause) =
+            generics.split_for_impl();
+
+        let prop_builder_fields = prop_builder_fields(vis, props);
+        let prop_docs = generate_component_fn_prop_docs(props);
+
+        let output = quote! {
+            #docs
+            #[doc = ""]
+            #prop_docs
+            #[derive(::tachys::typed_builder_macro::TypedBuilder)]
+            #[builder(crate_module_path=::tachys::typed_builder)]
+            #vis struct #name #impl_generics #where_clause {
+                #prop_builder_fields
+            }
+
+            impl #impl_generics From<#name #ty_generics> for Vec<#name #ty_generics> #where_clause {
+                fn from(value: #name #ty_generics) -> Self {
+                    vec![value]
+                }
+            }
+        };
+
+        tokens.append_all(output)
+    }
+}
@@ -1,4 +1,7 @@
-use super::{fragment_to_tokens, TagType};
+use super::{
+    attribute_value, children_to_tokens, directive_args, event_type_to_tokens,
+    TagType,
+};
 use proc_macro2::{Ident, TokenStream, TokenTree};
 use quote::{format_ident, quote};
 use rstml::node::{NodeAttribute, NodeElement, NodeName};
@@ -12,7 +15,6 @@ pub(crate) fn component_to_tokens(
     let name = node.name();
     #[cfg(debug_assertions)]
     let component_name = ident_from_tag_name(node.name());
-    let span = node.name().span();
 
     let attrs = node.attributes().iter().filter_map(|node| {
         if let NodeAttribute::Attribute(node) = node {
@@ -22,6 +24,96 @@ pub(crate) fn component_to_tokens(
         }
     });
 
+    // attributes, classes, event listeners and directives on the call site
+    // are forwarded onto the element at the root of the component's view
+    let forwarded = attrs
+        .filter_map(|attr| {
+            let name = attr.key.to_string();
+            if let Some(name) = name.strip_prefix("use:") {
+                let args = directive_args(name, attr);
+                Some(quote! {
+                    ::tachys::tachydom::html::directive::directive(#args)
+                })
+            } else if let Some(name) = name.strip_prefix("on:") {
+                let event_type = event_type_to_tokens(name, attr);
+                let handler = attribute_value(attr);
+                Some(quote! {
+                    ::tachys::tachydom::html::event::on(#event_type, #handler)
+                })
+            } else if let Some(name) = name.strip_prefix("class:") {
+                let value = attribute_value(attr);
+                Some(quote! {
+                    ::tachys::tachydom::html::class::class((#name, #value))
+                })
+            } else if let Some(name) = name.strip_prefix("attr:") {
+                let value = attribute_value(attr);
+                Some(match name {
+                    "class" => quote! {
+                        ::tachys::tachydom::html::class::class(#value)
+                    },
+                    "style" => quote! {
+                        ::tachys::tachydom::html::style::style(#value)
+                    },
+                    _ => quote! {
+                        ::tachys::tachydom::html::attribute::custom::custom_attribute(#name, #value)
+                    },
+                })
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let fields = builder_fields_to_tokens(node, global_class);
+
+    let generics = &node.open_tag.generics;
+    let generics = if generics.lt_token.is_some() {
+        quote! { ::#generics }
+    } else {
+        quote! {}
+    };
+
+    #[allow(unused_mut)] // used in debug
+    let mut component = quote! {
+        ::tachys::component::component_view(
+            &#name,
+            ::tachys::component::component_props_builder(&#name #generics)
+                #fields
+                .build()
+        )
+    };
+
+    // (Temporarily?) removed
+    // See note on the function itself below.
+    /* #[cfg(debug_assertions)]
+    IdeTagHelper::add_component_completion(&mut component, node); */
+
+    if forwarded.is_empty() {
+        component
+    } else {
+        quote! {
+            ::tachys::tachydom::html::element::AddAnyAttr::add_any_attr(
+                #component,
+                (#(#forwarded,)*)
+            )
+        }
+    }
+}
+
+/// The props, slots and children of a component or slot, as calls to the
+/// setters on its props builder.
+pub(crate) fn builder_fields_to_tokens(
+    node: &NodeElement,
+    global_class: Option<&TokenTree>,
+) -> TokenStream {
+    let attrs = node.attributes().iter().filter_map(|node| {
+        if let NodeAttribute::Attribute(node) = node {
+            Some(node)
+        } else {
+            None
+        }
+    });
+
     let props = attrs
         .clone()
         .filter(|attr| {
@@ -29,7 +121,10 @@ pub(crate) fn component_to_tokens(
                 && !attr.key.to_string().starts_with("clone:")
                 && !attr.key.to_string().starts_with("on:")
                 && !attr.key.to_string().starts_with("attr:")
+                && !attr.key.to_string().starts_with("class:")
                 && !attr.key.to_string().starts_with("use:")
+                && attr.key.to_string() != "slot"
+                && !attr.key.to_string().starts_with("slot:")
         })
         .map(|attr| {
             let name = &attr.key;
@@ -66,61 +161,23 @@ pub(crate) fn component_to_tokens(
         })
         .collect::<Vec<_>>();
 
-    // TODO events and directives
-    /*     let events = attrs
-        .clone()
-        .filter(|attr| attr.key.to_string().starts_with("on:"))
-        .map(|attr| {
-            let (event_type, handler) = event_from_attribute_node(attr, true);
-
-            quote! {
-                .on(#event_type, #handler)
-            }
-        })
-        .collect::<Vec<_>>();
-
-    let directives = attrs
-        .clone()
-        .filter_map(|attr| {
-            attr.key
-                .to_string()
-                .strip_prefix("use:")
-                .map(|ident| directive_call_from_attribute_node(attr, ident))
-        })
-        .collect::<Vec<_>>();
-
-    let events_and_directives =
-        events.into_iter().chain(directives).collect::<Vec<_>>(); */
-
-    let dyn_attrs = attrs
-        .filter(|attr| attr.key.to_string().starts_with("attr:"))
-        .filter_map(|attr| {
-            let name = &attr.key.to_string();
-            let name = name.strip_prefix("attr:");
-            let value = attr.value().map(|v| {
-                quote! { #v }
-            })?;
-            Some(quote! { (#name, #value.into_attribute()) })
-        })
-        .collect::<Vec<_>>();
-
-    let dyn_attrs = if dyn_attrs.is_empty() {
-        quote! {}
-    } else {
-        quote! { .dyn_attrs(vec![#(#dyn_attrs),*]) }
-    };
-
     let mut slots = HashMap::new();
     let children = if node.children.is_empty() {
         quote! {}
     } else {
-        let children = fragment_to_tokens(
+        // slots are taken out of the children and passed to their own props
+        let children = children_to_tokens(
             &node.children,
             TagType::Unknown,
             Some(&mut slots),
             global_class,
             None,
         );
+        let children = match children.len() {
+            0 => None,
+            1 => children.into_iter().next(),
+            _ => Some(quote! { (#(#children),*) }),
+        };
 
         if let Some(children) = children {
             let bindables =
@@ -153,7 +210,7 @@ pub(crate) fn component_to_tokens(
     };
 
     let slots = slots.drain().map(|(slot, values)| {
-        let slot = Ident::new(&slot, span);
+        let slot = Ident::new(&slot, node.name().span());
         if values.len() > 1 {
             quote! {
                 .#slot(vec![
@@ -166,41 +223,11 @@ pub(crate) fn component_to_tokens(
         }
     });
 
-    let generics = &node.open_tag.generics;
-    let generics = if generics.lt_token.is_some() {
-        quote! { ::#generics }
-    } else {
-        quote! {}
-    };
-
-    #[allow(unused_mut)] // used in debug
-    let mut component = quote! {
-        ::tachys::component::component_view(
-            &#name,
-            ::tachys::component::component_props_builder(&#name #generics)
-                #(#props)*
-                #(#slots)*
-                #children
-                .build()
-                #dyn_attrs
-        )
-    };
-
-    // (Temporarily?) removed
-    // See note on the function itself below.
-    /* #[cfg(debug_assertions)]
-    IdeTagHelper::add_component_completion(&mut component, node); */
-
-    // TODO events and directives
-    /* if events_and_directives.is_empty() {
-        component
-    } else {
-        quote! {
-            #component.into_view()
-            #(#events_and_directives)*
-        }
-    } */
-    component
+    quote! {
+        #(#props)*
+        #(#slots)*
+        #children
+    }
 }
 
 #[cfg(debug_assertions)]
@@ -1,14 +1,20 @@
 mod component_builder;
+mod slot_helper;
 
-use self::component_builder::component_to_tokens;
+use self::{
+    component_builder::component_to_tokens,
+    slot_helper::{get_slot, slot_to_tokens},
+};
 use convert_case::{Case::Snake, Casing};
 use leptos_hot_reload::parsing::is_component_node;
 use proc_macro2::{Ident, Span, TokenStream, TokenTree};
 use proc_macro_error::abort;
-use quote::{quote, quote_spanned, ToTokens};
-use rstml::node::{KeyedAttribute, Node, NodeAttribute, NodeElement, NodeName};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use rstml::node::{
+    KeyedAttribute, Node, NodeAttribute, NodeBlock, NodeElement, NodeName,
+};
 use std::collections::HashMap;
-use syn::{spanned::Spanned, Expr, ExprPath, Lit, LitStr};
+use syn::{spanned::Spanned, Expr, ExprPath, Lit, LitStr, RangeLimits, Stmt};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub(crate) enum TagType {
@@ -117,13 +123,14 @@ fn children_to_tokens(
             None => vec![],
         }
     } else {
+        let mut parent_slots = parent_slots;
         nodes
             .iter()
             .filter_map(|node| {
                 node_to_tokens(
                     node,
                     TagType::Unknown,
-                    None,
+                    parent_slots.as_deref_mut(),
                     global_class,
                     view_marker,
                 )
@@ -191,13 +198,12 @@ pub(crate) fn element_to_tokens(
 ) -> Option<TokenStream> {
     let name = node.name();
     if is_component_node(node) {
-        // TODO slots
-        /* if let Some(slot) = get_slot(node) {
+        if let Some(slot) = get_slot(node) {
             slot_to_tokens(node, slot, parent_slots, global_class);
             None
-        } else { */
-        Some(component_to_tokens(node, global_class))
-        //}
+        } else {
+            Some(component_to_tokens(node, global_class))
+        }
     } else {
         let tag = name.to_string();
         // collect close_tag name to emit semantic information for IDE.
@@ -300,7 +306,7 @@ fn attribute_to_tokens(
     global_class: Option<&TokenTree>,
 ) -> TokenStream {
     match node {
-        NodeAttribute::Block(_) => todo!(),
+        NodeAttribute::Block(block) => spread_to_tokens(block),
         NodeAttribute::Attribute(node) => {
             let name = node.key.to_string();
             if name == "node_ref" {
@@ -314,6 +320,8 @@ fn attribute_to_tokens(
                 }
             } else if let Some(name) = name.strip_prefix("on:") {
                 event_to_tokens(name, node)
+            } else if let Some(name) = name.strip_prefix("use:") {
+                directive_to_tokens(name, node)
             } else if let Some(name) = name.strip_prefix("class:") {
                 let class = match &node.key {
                     NodeName::Punctuated(parts) => &parts[0],
@@ -363,10 +371,63 @@ fn attribute_to_tokens(
     }
 }
 
+// `{..attrs}` spreads a whole set of attributes onto the element
+fn spread_to_tokens(block: &NodeBlock) -> TokenStream {
+    if let NodeBlock::ValidBlock(block) = block {
+        if let [Stmt::Expr(Expr::Range(range), None)] = block.stmts.as_slice() {
+            if let (None, RangeLimits::HalfOpen(_), Some(end)) =
+                (&range.start, &range.limits, &range.end)
+            {
+                return quote! {
+                    .spread(#end)
+                };
+            }
+        }
+    }
+    proc_macro_error::emit_error!(
+        block.span(),
+        "only spreads like `{..attrs}` can be used as attributes"
+    );
+    quote! {}
+}
+
+fn directive_to_tokens(name: &str, node: &KeyedAttribute) -> TokenStream {
+    let args = directive_args(name, node);
+    quote! {
+        .directive(#args)
+    }
+}
+
+// the handler and value of `use:handler=value`, which is `()` if left out
+pub(crate) fn directive_args(name: &str, node: &KeyedAttribute) -> TokenStream {
+    let handler = format_ident!("{name}", span = node.key.span());
+    let param = node
+        .value()
+        .map(|value| quote! { #value })
+        .unwrap_or_else(|| quote! { () });
+    quote! {
+        #handler, #[allow(unused_braces)] {#param}
+    }
+}
+
 fn event_to_tokens(name: &str, node: &KeyedAttribute) -> TokenStream {
     let handler = attribute_value(node);
+    let on = match &node.key {
+        NodeName::Punctuated(parts) => &parts[0],
+        _ => unreachable!(),
+    };
+    let event_type = event_type_to_tokens(name, node);
+
+    quote! {
+        .#on(#event_type, #handler)
+    }
+}
 
-    let (event_type, is_custom, is_force_undelegated) = parse_event_name(name);
+pub(crate) fn event_type_to_tokens(
+    name: &str,
+    node: &KeyedAttribute,
+) -> TokenStream {
+    let (event_type, is_custom, modifiers) = parse_event_name(name);
 
     let event_name_ident = match &node.key {
         NodeName::Punctuated(parts) => {
@@ -378,20 +439,6 @@ fn event_to_tokens(name: &str, node: &KeyedAttribute) -> TokenStream {
         }
         _ => unreachable!(),
     };
-    let undelegated_ident = match &node.key {
-        NodeName::Punctuated(parts) => parts.last().and_then(|last| {
-            if last.to_string() == "undelegated" {
-                Some(last)
-            } else {
-                None
-            }
-        }),
-        _ => unreachable!(),
-    };
-    let on = match &node.key {
-        NodeName::Punctuated(parts) => &parts[0],
-        _ => unreachable!(),
-    };
     let event_type = if is_custom {
         event_type
     } else if let Some(ev_name) = event_name_ident {
@@ -402,24 +449,32 @@ fn event_to_tokens(name: &str, node: &KeyedAttribute) -> TokenStream {
     } else {
         event_type
     };
+    let event_type = quote! { ::tachys::tachydom::html::event::#event_type };
 
-    let event_type = if is_force_undelegated {
-        let undelegated = if let Some(undelegated) = undelegated_ident {
-            let span = undelegated.span();
-            quote_spanned! {
-                span => #undelegated
-            }
-        } else {
-            quote! { undelegated }
-        };
-        // TODO undelegated
-        quote! { ::tachys::tachydom::html::event::#undelegated(::tachys::tachydom::html::event::#event_type) }
-    } else {
-        quote! { ::tachys::tachydom::html::event::#event_type }
-    };
+    if modifiers.is_empty() {
+        return event_type;
+    }
 
+    // any modifier means the listener is added directly to the element
+    let span = node.key.span();
+    let options = modifiers.into_iter().filter_map(|modifier| match modifier {
+        "undelegated" => None,
+        "capture" | "passive" | "once" => {
+            let option = Ident::new(modifier, span);
+            Some(quote! { .#option() })
+        }
+        _ => {
+            proc_macro_error::emit_error!(
+                span,
+                "unknown event modifier `{}`", modifier;
+                help = "use `undelegated`, `capture`, `passive` or `once`"
+            );
+            None
+        }
+    });
     quote! {
-        .#on(#event_type, #handler)
+        ::tachys::tachydom::html::event::undelegated(#event_type)
+            #(#options)*
     }
 }
 
@@ -597,12 +652,11 @@ fn is_ambiguous_element(tag: &str) -> bool {
     tag == "a" || tag == "script" || tag == "title"
 }
 
-fn parse_event(event_name: &str) -> (&str, bool) {
-    if let Some(event_name) = event_name.strip_suffix(":undelegated") {
-        (event_name, true)
-    } else {
-        (event_name, false)
-    }
+// splits `click:capture:once` into the event name and its modifiers
+fn parse_event(event_name: &str) -> (&str, Vec<&str>) {
+    let mut parts = event_name.split(':');
+    let event_name = parts.next().unwrap_or_default();
+    (event_name, parts.collect())
 }
 
 /// Escapes Rust keywords that are also HTML attribute names
@@ -618,7 +672,7 @@ fn attribute_name(name: &NodeName) -> TokenStream {
     }
 }
 
-fn attribute_value(attr: &KeyedAttribute) -> TokenStream {
+pub(crate) fn attribute_value(attr: &KeyedAttribute) -> TokenStream {
     match attr.value() {
         Some(value) => {
             if let Expr::Lit(lit) = value {
@@ -768,8 +822,8 @@ const TYPED_EVENTS: [&str; 126] = [
 
 const CUSTOM_EVENT: &str = "Custom";
 
-pub(crate) fn parse_event_name(name: &str) -> (TokenStream, bool, bool) {
-    let (name, is_force_undelegated) = parse_event(name);
+pub(crate) fn parse_event_name(name: &str) -> (TokenStream, bool, Vec<&str>) {
+    let (name, modifiers) = parse_event(name);
 
     let (event_type, is_custom) = TYPED_EVENTS
         .binary_search(&name)
@@ -785,7 +839,7 @@ pub(crate) fn parse_event_name(name: &str) -> (TokenStream, bool, bool) {
     } else {
         event_type
     };
-    (event_type, is_custom, is_force_undelegated)
+    (event_type, is_custom, modifiers)
 }
 
 fn expr_to_ident(expr: &syn::Expr) -> Option<&ExprPath> {
@@ -0,0 +1,54 @@
+use super::component_builder::builder_fields_to_tokens;
+use convert_case::{Case::Snake, Casing};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::quote;
+use rstml::node::{KeyedAttribute, NodeAttribute, NodeElement};
+use std::collections::HashMap;
+use syn::spanned::Spanned;
+
+/// The `slot` or `slot:name` attribute that marks a component node as a slot.
+pub(crate) fn get_slot(node: &NodeElement) -> Option<&KeyedAttribute> {
+    node.attributes().iter().find_map(|attr| match attr {
+        NodeAttribute::Attribute(attr) => {
+            let key = attr.key.to_string();
+            (key == "slot" || key.starts_with("slot:")).then_some(attr)
+        }
+        _ => None,
+    })
+}
+
+/// Builds a slot and adds it to the slots of the component it is passed to.
+///
+/// `<Then slot>` fills the parent's `then` prop, and `<Then slot:on_true>`
+/// fills its `on_true` prop instead.
+pub(crate) fn slot_to_tokens(
+    node: &NodeElement,
+    slot: &KeyedAttribute,
+    parent_slots: Option<&mut HashMap<String, Vec<TokenStream>>>,
+    global_class: Option<&TokenTree>,
+) {
+    let name = node.name();
+    let Some(parent_slots) = parent_slots else {
+        proc_macro_error::emit_error!(
+            name.span(),
+            "slots can only be used as children of a component"
+        );
+        return;
+    };
+
+    let key = slot.key.to_string();
+    let slot_name = match key.strip_prefix("slot:") {
+        Some(slot_name) => slot_name.to_string(),
+        None => name.to_string().to_case(Snake),
+    };
+
+    let fields = builder_fields_to_tokens(node, global_class);
+    let slot = quote! {
+        #name::builder()
+            #fields
+            .build()
+            .into(),
+    };
+
+    parent_slots.entry(slot_name).or_default().push(slot);
+}
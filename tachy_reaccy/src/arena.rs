@@ -202,6 +202,31 @@ impl Owner {
     pub fn current() -> Option<Owner> {
         OWNER.with(|o| o.borrow().clone())
     }
+
+    /// Returns a weak reference to this owner, which does not keep it alive.
+    pub fn downgrade(&self) -> WeakOwner {
+        WeakOwner {
+            inner: Arc::downgrade(&self.inner),
+            shared_context: self.shared_context.clone(),
+        }
+    }
+}
+
+/// A weak reference to an [`Owner`], created with [`Owner::downgrade`].
+#[derive(Debug, Clone)]
+pub struct WeakOwner {
+    inner: Weak<RwLock<OwnerInner>>,
+    shared_context: Option<Arc<dyn SharedContext + Send + Sync>>,
+}
+
+impl WeakOwner {
+    /// Returns the owner, unless it has already been dropped.
+    pub fn upgrade(&self) -> Option<Owner> {
+        self.inner.upgrade().map(|inner| Owner {
+            inner,
+            shared_context: self.shared_context.clone(),
+        })
+    }
 }
 
 #[derive(Default)]
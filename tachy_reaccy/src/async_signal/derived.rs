@@ -1,6 +1,7 @@
-use super::{AsyncState, ScopedFuture};
+use super::{AsyncState, ScopedFuture, SuspenseContext, SuspenseTask};
 use crate::{
     arena::{Owner, Stored, StoredData},
+    context::use_context,
     notify::{channel, Sender},
     prelude::{DefinedAt, SignalWithUntracked},
     source::{
@@ -225,6 +226,7 @@ impl<T: 'static> ArcAsyncDerived<T> {
             source: self.to_any_source(),
             value: Arc::clone(&self.value),
             wakers: Arc::clone(&self.wakers),
+            suspense: None,
         }
     }
 }
@@ -353,20 +355,27 @@ pub struct AsyncDerivedReadyFuture<T> {
     source: AnySource,
     value: Arc<RwLock<AsyncState<T>>>,
     wakers: Arc<RwLock<Vec<Waker>>>,
+    // keeps any surrounding suspense boundary pending while this waits
+    suspense: Option<SuspenseTask>,
 }
 
 impl<T: 'static> Future for AsyncDerivedReadyFuture<T> {
     type Output = ();
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
         let waker = cx.waker();
-        self.source.track();
-        match &*self.value.read() {
+        this.source.track();
+        match &*this.value.read() {
             AsyncState::Loading | AsyncState::Reloading(_) => {
-                self.wakers.write().push(waker.clone());
+                this.wakers.write().push(waker.clone());
+                suspend_boundary(&mut this.suspense);
                 Poll::Pending
             }
-            AsyncState::Complete(_) => Poll::Ready(()),
+            AsyncState::Complete(_) => {
+                this.suspense = None;
+                Poll::Ready(())
+            }
         }
     }
 }
@@ -377,6 +386,8 @@ pub struct AsyncDerivedFuture<T> {
     source: AnySource,
     value: Arc<RwLock<AsyncState<T>>>,
     wakers: Arc<RwLock<Vec<Waker>>>,
+    // keeps any surrounding suspense boundary pending while this waits
+    suspense: Option<SuspenseTask>,
 }
 
 impl<T: Clone + 'static> IntoFuture for ArcAsyncDerived<T> {
@@ -388,6 +399,7 @@ impl<T: Clone + 'static> IntoFuture for ArcAsyncDerived<T> {
             source: self.to_any_source(),
             value: Arc::clone(&self.value),
             wakers: Arc::clone(&self.wakers),
+            suspense: None,
         }
     }
 }
@@ -396,18 +408,31 @@ impl<T: Clone + 'static> Future for AsyncDerivedFuture<T> {
     type Output = T;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
         let waker = cx.waker();
-        self.source.track();
-        match &*self.value.read() {
+        this.source.track();
+        match &*this.value.read() {
             AsyncState::Loading | AsyncState::Reloading(_) => {
-                self.wakers.write().push(waker.clone());
+                this.wakers.write().push(waker.clone());
+                suspend_boundary(&mut this.suspense);
                 Poll::Pending
             }
-            AsyncState::Complete(value) => Poll::Ready(value.clone()),
+            AsyncState::Complete(value) => {
+                this.suspense = None;
+                Poll::Ready(value.clone())
+            }
         }
     }
 }
 
+// registers a future that is waiting for its value with the suspense boundary
+// it is polled in, if any, the first time it has to wait
+fn suspend_boundary(task: &mut Option<SuspenseTask>) {
+    if task.is_none() {
+        *task = use_context::<SuspenseContext>().map(|ctx| ctx.task());
+    }
+}
+
 pub struct AsyncDerived<T: Send + Sync + 'static> {
     inner: Stored<ArcAsyncDerived<T>>,
 }
@@ -1,5 +1,6 @@
 mod derived;
 mod resource;
+mod suspense;
 use crate::{arena::Owner, source::AnySubscriber, Observer};
 pub use derived::*;
 use futures::Future;
@@ -9,6 +10,7 @@ use std::{
     pin::Pin,
     task::{Context, Poll},
 };
+pub use suspense::*;
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub enum AsyncState<T> {
@@ -10,9 +10,11 @@ use crate::{
     prelude::SignalWithUntracked,
     serialization::{SerdeJson, SerializableData, Serializer, Str},
     shared_context::SerializedDataId,
+    PinnedFuture,
 };
 use core::{fmt::Debug, marker::PhantomData};
 use futures::Future;
+use parking_lot::Mutex;
 use std::{future::IntoFuture, ops::Deref};
 
 pub struct ArcResource<T, Ser> {
@@ -95,9 +97,9 @@ where
 }
 
 #[cfg(feature = "rkyv")]
-impl<T> ArcResource<T, SerdeLite>
+impl<T> ArcResource<T, Rkyv>
 where
-    T: SerializableData<SerdeLite>,
+    T: SerializableData<Rkyv>,
     T::SerErr: Debug,
     T::DeErr: Debug,
 {
@@ -129,8 +131,11 @@ where
             .unwrap_or_default();
 
         let initial = Self::initial_value(&id);
+        let streamed = Mutex::new(Self::streamed_value(&id, &initial));
 
-        let data = ArcAsyncDerived::new_with_initial(initial, fun);
+        let data = ArcAsyncDerived::new_with_initial(initial, move || {
+            Self::streamed_or_fetch(streamed.lock().take(), fun())
+        });
 
         if let Some(shared_context) = Owner::shared_context() {
             let value = data.clone();
@@ -176,6 +181,53 @@ where
         }
         AsyncState::Loading
     }
+
+    // if the server is still streaming this resource's data, the first run
+    // waits for that rather than fetching it again
+    #[inline(always)]
+    fn streamed_value(
+        id: &SerializedDataId,
+        initial: &AsyncState<T>,
+    ) -> Option<PinnedFuture<String>> {
+        #[cfg(feature = "hydration")]
+        {
+            if matches!(initial, AsyncState::Loading) {
+                return Owner::shared_context()
+                    .and_then(|sc| sc.await_data(id));
+            }
+        }
+        _ = (id, initial);
+        None
+    }
+
+    // waits for the value streamed from the server, if there is one, and
+    // otherwise runs `fetch`
+    //
+    // `fetch` is always created, so that any signals read while creating it
+    // are tracked, even if it is never polled
+    fn streamed_or_fetch<Fut>(
+        streamed: Option<PinnedFuture<String>>,
+        fetch: Fut,
+    ) -> PinnedFuture<T>
+    where
+        T: Send + Sync + 'static,
+        Fut: Future<Output = T> + Send + Sync + 'static,
+    {
+        Box::pin(async move {
+            if let Some(streamed) = streamed {
+                let value = streamed.await;
+                match T::de(&value) {
+                    Ok(value) => return value,
+                    Err(e) => {
+                        crate::log(&format!(
+                            "couldn't deserialize from {value:?}: {e:?}"
+                        ));
+                    }
+                }
+            }
+            fetch.await
+        })
+    }
 }
 
 impl<T, Ser> IntoFuture for ArcResource<T, Ser>
@@ -318,8 +370,15 @@ where
             .unwrap_or_default();
 
         let initial = Self::initial_value(&id);
-
-        let data = AsyncDerived::new_with_initial(initial, fun);
+        let streamed =
+            Mutex::new(ArcResource::<T, Ser>::streamed_value(&id, &initial));
+
+        let data = AsyncDerived::new_with_initial(initial, move || {
+            ArcResource::<T, Ser>::streamed_or_fetch(
+                streamed.lock().take(),
+                fun(),
+            )
+        });
 
         if let Some(shared_context) = Owner::shared_context() {
             let value = data;
@@ -0,0 +1,75 @@
+use crate::{
+    signal::ArcRwSignal,
+    signal_traits::{SignalGet, SignalUpdate},
+};
+use std::fmt::Debug;
+
+/// Tracks the async work beneath a suspense boundary.
+///
+/// A boundary provides this through context. Anything that starts waiting on
+/// async data inside it, like a suspended view or a `.await` on an
+/// [`AsyncDerived`](super::AsyncDerived) or [`Resource`](super::Resource),
+/// registers a [`SuspenseTask`] for as long as it is waiting, so that the
+/// boundary knows when all of its content is ready.
+#[derive(Clone)]
+pub struct SuspenseContext {
+    tasks: ArcRwSignal<usize>,
+}
+
+impl Debug for SuspenseContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SuspenseContext")
+            .field("tasks", &self.tasks)
+            .finish()
+    }
+}
+
+impl Default for SuspenseContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SuspenseContext {
+    pub fn new() -> Self {
+        Self {
+            tasks: ArcRwSignal::new(0),
+        }
+    }
+
+    /// Registers a piece of async work, which is pending until the returned
+    /// task is dropped.
+    pub fn task(&self) -> SuspenseTask {
+        self.tasks.update(|n| *n += 1);
+        SuspenseTask {
+            tasks: self.tasks.clone(),
+        }
+    }
+
+    /// Whether any of the work beneath the boundary is still pending.
+    ///
+    /// This is reactive, so the boundary can switch between its fallback and
+    /// its content as work starts and finishes.
+    pub fn pending(&self) -> bool {
+        self.tasks.get() > 0
+    }
+}
+
+/// A piece of async work registered with a [`SuspenseContext`], which is no
+/// longer pending once this is dropped.
+#[must_use]
+pub struct SuspenseTask {
+    tasks: ArcRwSignal<usize>,
+}
+
+impl Debug for SuspenseTask {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SuspenseTask").finish_non_exhaustive()
+    }
+}
+
+impl Drop for SuspenseTask {
+    fn drop(&mut self) {
+        self.tasks.update(|n| *n = n.saturating_sub(1));
+    }
+}
@@ -3,6 +3,7 @@ pub mod async_signal;
 pub mod context;
 pub mod effect;
 pub mod memo;
+pub mod nonce;
 mod notify;
 pub mod render_effect;
 pub mod selector;
@@ -16,7 +17,7 @@ mod source;
 pub mod spawn;
 pub mod store;
 use crate::source::AnySubscriber;
-pub use arena::{Owner, Root};
+pub use arena::{Owner, Root, WeakOwner};
 use futures::{Future, Stream};
 use std::{cell::RefCell, pin::Pin};
 
@@ -26,6 +27,7 @@ pub mod prelude {
         context::{provide_context, use_context},
         effect::Effect,
         memo::{ArcMemo, Memo},
+        nonce::{provide_nonce, use_nonce, Nonce},
         signal::{signal, ArcRwSignal, ReadSignal, RwSignal},
         signal_traits::*,
         store::{StoreField, StoreFieldIndex, StoreFieldIterator},
@@ -0,0 +1,61 @@
+use crate::context::{provide_context, use_context};
+use std::{fmt, ops::Deref, sync::Arc};
+
+/// A cryptographic nonce ("number used once") which can be used by a
+/// [Content Security Policy](https://developer.mozilla.org/en-US/docs/Web/HTTP/CSP)
+/// to allow specific inline scripts and resources to load.
+///
+/// A new nonce should be generated for each response, and sent in the
+/// `Content-Security-Policy` header. Once it has been provided with
+/// [`provide_nonce`], it is added to every inline `<script>` that server
+/// rendering emits, and can be read with [`use_nonce`] to add it elsewhere.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Nonce(Arc<str>);
+
+impl Nonce {
+    /// Wraps the given value as a nonce.
+    pub fn new(value: impl Into<Arc<str>>) -> Self {
+        Self(value.into())
+    }
+
+    /// Returns the value of the nonce.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Nonce {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for Nonce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<String> for Nonce {
+    fn from(value: String) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&str> for Nonce {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+/// Provides the nonce for the current response through context.
+pub fn provide_nonce(nonce: impl Into<Nonce>) {
+    provide_context(nonce.into());
+}
+
+/// Returns the nonce for the current response, if one has been provided.
+pub fn use_nonce() -> Option<Nonce> {
+    use_context::<Nonce>()
+}
@@ -143,13 +143,15 @@ mod rkyv {
     use rkyv::{
         de::deserializers::SharedDeserializeMap,
         ser::serializers::AllocSerializer,
-        validation::validators::DefaultValidator, Archive, CheckBytes,
-        Deserialize, Serialize,
+        validation::validators::DefaultValidator, AlignedVec, Archive,
+        CheckBytes, Deserialize, Serialize,
     };
-    use std::{error::Error, rc::Rc};
+    use std::{error::Error, marker::PhantomData, rc::Rc};
     use thiserror::Error;
 
     /// A [`Serializer`] that serializes and deserializes using [`rkyv`].
+    ///
+    /// The binary data is sent to the client as a base64 string.
     pub struct Rkyv;
 
     impl Serializer for Rkyv {}
@@ -160,6 +162,8 @@ mod rkyv {
         Rkyv(Rc<dyn Error>),
         #[error("base64 error {0:?}")]
         Base64Decode(base64::DecodeError),
+        #[error("invalid archived data: {0}")]
+        Invalid(String),
     }
 
     impl From<Rc<dyn Error>> for RkyvError {
@@ -174,6 +178,14 @@ mod rkyv {
         }
     }
 
+    // archived data has to be read from a correctly-aligned buffer
+    fn decode(data: &str) -> Result<AlignedVec, RkyvError> {
+        let decoded = STANDARD_NO_PAD.decode(data.as_bytes())?;
+        let mut bytes = AlignedVec::with_capacity(decoded.len());
+        bytes.extend_from_slice(&decoded);
+        Ok(bytes)
+    }
+
     impl<T> SerializableData<Rkyv> for T
     where
         T: Serialize<AllocSerializer<1024>>,
@@ -191,11 +203,107 @@ mod rkyv {
         }
 
         fn de(data: &str) -> Result<Self, Self::DeErr> {
-            let bytes = STANDARD_NO_PAD.decode(data.as_bytes())?;
+            let bytes = decode(data)?;
             Ok(rkyv::from_bytes::<T>(&bytes)
                 .map_err(|e| Rc::new(e) as Rc<dyn Error>)?)
         }
     }
+
+    /// A value serialized with [`rkyv`], which can be read in its archived
+    /// form without deserializing it.
+    ///
+    /// The bytes are validated once, when they are received. After that,
+    /// [`ArchivedData::get`] gives zero-copy access to the archived value.
+    pub struct ArchivedData<T> {
+        bytes: AlignedVec,
+        ty: PhantomData<T>,
+    }
+
+    impl<T> ArchivedData<T>
+    where
+        T: Archive,
+        T::Archived: for<'b> CheckBytes<DefaultValidator<'b>>,
+    {
+        /// Serializes the given value.
+        pub fn new(value: &T) -> Result<Self, RkyvError>
+        where
+            T: Serialize<AllocSerializer<1024>>,
+        {
+            let bytes = rkyv::to_bytes::<T, 1024>(value)
+                .map_err(|e| Rc::new(e) as Rc<dyn Error>)?;
+            Ok(Self {
+                bytes,
+                ty: PhantomData,
+            })
+        }
+
+        /// Validates that the given bytes contain an archived `T`.
+        pub fn from_bytes(bytes: AlignedVec) -> Result<Self, RkyvError> {
+            rkyv::check_archived_root::<T>(&bytes)
+                .map_err(|e| RkyvError::Invalid(e.to_string()))?;
+            Ok(Self {
+                bytes,
+                ty: PhantomData,
+            })
+        }
+
+        /// Returns the archived value.
+        pub fn get(&self) -> &T::Archived {
+            // SAFETY: the bytes were either serialized from a `T`, or
+            // validated as an archived `T` when this was created
+            unsafe { rkyv::archived_root::<T>(&self.bytes) }
+        }
+
+        /// Returns the serialized bytes.
+        pub fn as_bytes(&self) -> &[u8] {
+            &self.bytes
+        }
+
+        /// Deserializes the archived value.
+        pub fn deserialize(&self) -> Result<T, RkyvError>
+        where
+            T::Archived: Deserialize<T, SharedDeserializeMap>,
+        {
+            Ok(self
+                .get()
+                .deserialize(&mut SharedDeserializeMap::new())
+                .map_err(|e| Rc::new(e) as Rc<dyn Error>)?)
+        }
+    }
+
+    impl<T> Clone for ArchivedData<T> {
+        fn clone(&self) -> Self {
+            Self {
+                bytes: self.bytes.clone(),
+                ty: PhantomData,
+            }
+        }
+    }
+
+    impl<T> std::fmt::Debug for ArchivedData<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("ArchivedData")
+                .field("len", &self.bytes.len())
+                .finish()
+        }
+    }
+
+    impl<T> SerializableData<Rkyv> for ArchivedData<T>
+    where
+        T: Archive,
+        T::Archived: for<'b> CheckBytes<DefaultValidator<'b>>,
+    {
+        type SerErr = RkyvError;
+        type DeErr = RkyvError;
+
+        fn ser(&self) -> Result<String, Self::SerErr> {
+            Ok(STANDARD_NO_PAD.encode(&self.bytes))
+        }
+
+        fn de(data: &str) -> Result<Self, Self::DeErr> {
+            Self::from_bytes(decode(data)?)
+        }
+    }
 }
 
 #[cfg(feature = "rkyv")]
@@ -1,13 +1,16 @@
 use super::{SerializedDataId, SharedContext};
 use crate::{PinnedFuture, PinnedStream};
 use core::fmt::Debug;
+use futures::channel::oneshot;
 use js_sys::Array;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{closure::Closure, prelude::wasm_bindgen, JsValue};
 
 #[wasm_bindgen]
 extern "C" {
     static __RESOLVED_RESOURCES: Array;
+    static __PENDING_RESOURCES: Array;
+    static __RESOURCE_RESOLVERS: Array;
 }
 
 #[derive(Default)]
@@ -50,8 +53,31 @@ impl SharedContext for HydrateSharedContext {
         __RESOLVED_RESOURCES.get(id.0 as u32).as_string()
     }
 
-    fn await_data(&self, _id: &SerializedDataId) -> Option<String> {
-        todo!()
+    fn await_data(
+        &self,
+        id: &SerializedDataId,
+    ) -> Option<PinnedFuture<String>> {
+        let id = id.0 as u32;
+        if let Some(data) = __RESOLVED_RESOURCES.get(id).as_string() {
+            return Some(Box::pin(async move { data }));
+        }
+        if __PENDING_RESOURCES.index_of(&JsValue::from(id), 0) == -1 {
+            return None;
+        }
+
+        // the server calls this resolver from the <script> that sends the
+        // data, once the resource has resolved there
+        let (tx, rx) = oneshot::channel();
+        let resolver = Closure::once_into_js(move || {
+            if let Some(data) = __RESOLVED_RESOURCES.get(id).as_string() {
+                _ = tx.send(data);
+            }
+        });
+        __RESOURCE_RESOLVERS.set(id, resolver);
+
+        // if the resolver runs without any data, this resolves with an empty
+        // string, which the resource will fail to deserialize and fetch instead
+        Some(Box::pin(async move { rx.await.unwrap_or_default() }))
     }
 
     fn pending_data(&self) -> Option<PinnedStream<String>> {
@@ -33,15 +33,19 @@ pub trait SharedContext: Debug {
 
     /// Returns a [`Future`] that resolves with a `String` that should
     /// be deserialized using [`Serializable::de`] once the given piece of server
-    /// data has resolved.
+    /// data has resolved, if the server is still going to send it.
     ///
     /// On the server and in client-side rendered implementations, this should
-    /// return a [`Future`] that is immediately ready with [`None`].
-    fn await_data(&self, id: &SerializedDataId) -> Option<String>;
+    /// always return [`None`].
+    fn await_data(&self, id: &SerializedDataId)
+        -> Option<PinnedFuture<String>>;
 
-    /// Returns some [`Stream`] of HTML that contains JavaScript `<script>` tags defining
-    /// all values being serialized from the server to the client, with their serialized values
-    /// and any boilerplate needed to notify a running application that they exist; or `None`.
+    /// Returns some [`Stream`] of JavaScript snippets defining all values being serialized from
+    /// the server to the client, with their serialized values and any boilerplate needed to
+    /// notify a running application that they exist; or `None`.
+    ///
+    /// Each snippet should be sent in its own inline `<script>` tag, which is left to the
+    /// renderer so that it can add the nonce for the response.
     ///
     /// In browser implementations, this return `None`.
     fn pending_data(&self) -> Option<PinnedStream<String>>;
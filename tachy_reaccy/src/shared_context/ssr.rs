@@ -15,6 +15,8 @@ use std::{
 pub struct SsrSharedContext {
     id: AtomicUsize,
     is_hydrating: AtomicBool,
+    // whether the globals used to send data to the client have been set up
+    initialized: AtomicBool,
     sync_buf: RwLock<Vec<ResolvedData>>,
     async_buf: RwLock<Vec<(SerializedDataId, PinnedFuture<String>)>>,
 }
@@ -61,36 +63,56 @@ impl SharedContext for SsrSharedContext {
         let async_data = mem::take(&mut *self.async_buf.write());
 
         // 1) initial, synchronous setup chunk
+        // this is only sent in full the first time, because any later calls
+        // pick up resources that were created while streaming the response
         let mut initial_chunk = String::new();
-        // resolved synchronous resources
-        initial_chunk.push_str("__RESOLVED_RESOURCES=[");
-        for resolved in sync_data {
-            resolved.write_to_buf(&mut initial_chunk);
-            initial_chunk.push(',');
+        if !self.initialized.swap(true, Ordering::Relaxed) {
+            // resolved synchronous resources
+            initial_chunk.push_str("__RESOLVED_RESOURCES=[];");
+            for resolved in sync_data {
+                resolved.write_to_buf(&mut initial_chunk);
+            }
+
+            // pending async resources
+            initial_chunk.push_str("__PENDING_RESOURCES=[");
+            for (id, _) in &async_data {
+                write!(&mut initial_chunk, "{},", id.0).unwrap();
+            }
+            initial_chunk.push_str("];");
+
+            // resolvers
+            initial_chunk.push_str("__RESOURCE_RESOLVERS=[];");
+        } else {
+            for resolved in sync_data {
+                resolved.write_to_buf(&mut initial_chunk);
+            }
+            if !async_data.is_empty() {
+                initial_chunk.push_str("__PENDING_RESOURCES.push(");
+                for (id, _) in &async_data {
+                    write!(&mut initial_chunk, "{},", id.0).unwrap();
+                }
+                initial_chunk.push_str(");");
+            }
         }
-        initial_chunk.push_str("];");
-
-        // pending async resources
-        initial_chunk.push_str("__PENDING_RESOURCES=[");
-        for (id, _) in &async_data {
-            write!(&mut initial_chunk, "{},", id.0).unwrap();
-        }
-        initial_chunk.push_str("];");
-
-        // resolvers
-        initial_chunk.push_str("__RESOURCE_RESOLVERS=[];");
 
         // 2) async resources as they resolve
         let async_data = async_data
             .into_iter()
             .map(|(id, data)| async move {
                 let data = data.await;
-                format!("__RESOLVED_RESOURCES[{}] = {data:?};", id.0)
+                let mut chunk = String::new();
+                let id_num = id.0;
+                ResolvedData(id, data).write_to_buf(&mut chunk);
+                // wakes up the resource on the client, if it's waiting
+                write!(&mut chunk, "__RESOURCE_RESOLVERS[{id_num}]?.();")
+                    .unwrap();
+                chunk
             })
             .collect::<FuturesUnordered<_>>();
 
-        let stream =
-            stream::once(async move { initial_chunk }).chain(async_data);
+        let initial_chunk =
+            (!initial_chunk.is_empty()).then_some(initial_chunk);
+        let stream = stream::iter(initial_chunk).chain(async_data);
         Some(Box::pin(stream))
     }
 
@@ -98,7 +120,10 @@ impl SharedContext for SsrSharedContext {
         None
     }
 
-    fn await_data(&self, _id: &SerializedDataId) -> Option<String> {
+    fn await_data(
+        &self,
+        _id: &SerializedDataId,
+    ) -> Option<PinnedFuture<String>> {
         None
     }
 
@@ -118,7 +143,7 @@ impl ResolvedData {
     pub fn write_to_buf(&self, buf: &mut String) {
         let ResolvedData(id, ser) = self;
         // escapes < to prevent it being interpreted as another opening HTML tag
-        let ser = ser.replace('<', "\\u003c");
-        write!(buf, "{}: {:?}", id.0, ser).unwrap();
+        let ser = format!("{ser:?}").replace('<', "\\u003c");
+        write!(buf, "__RESOLVED_RESOURCES[{}] = {ser};", id.0).unwrap();
     }
 }
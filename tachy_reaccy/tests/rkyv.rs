@@ -0,0 +1,75 @@
+#![cfg(feature = "rkyv")]
+
+use futures::StreamExt;
+use rkyv::{Archive, Deserialize, Serialize};
+use std::sync::Arc;
+use tachy_reaccy::{
+    async_signal::ArcResource,
+    serialization::{ArchivedData, Rkyv, SerializableData},
+    shared_context::{SharedContext, SsrSharedContext},
+    Root,
+};
+
+#[derive(Archive, Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[archive(check_bytes)]
+struct Post {
+    id: u32,
+    title: String,
+    body: Vec<u8>,
+}
+
+fn post() -> Post {
+    Post {
+        id: 7,
+        title: String::from("Binary"),
+        body: vec![0, 1, 2, 255],
+    }
+}
+
+// pulls the serialized value for the given resource out of the data script
+fn resolved_data(html: &str, id: usize) -> &str {
+    let start = format!("__RESOLVED_RESOURCES[{id}] = \"");
+    let (_, rest) = html.split_once(&start).unwrap();
+    rest.split_once('"').unwrap().0
+}
+
+#[test]
+fn rkyv_round_trips_through_base64() {
+    let data = <Post as SerializableData<Rkyv>>::ser(&post()).unwrap();
+    assert!(data
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/'));
+    assert_eq!(<Post as SerializableData<Rkyv>>::de(&data).unwrap(), post());
+}
+
+#[test]
+fn archived_data_is_validated_once_and_read_in_place() {
+    let data = ArchivedData::new(&post()).unwrap().ser().unwrap();
+    let archived = ArchivedData::<Post>::de(&data).unwrap();
+
+    let value = archived.get();
+    assert_eq!(value.id, 7);
+    assert_eq!(value.title.as_str(), "Binary");
+    assert_eq!(value.body.as_slice(), &[0, 1, 2, 255]);
+    assert_eq!(archived.deserialize().unwrap(), post());
+
+    // bytes that aren't a valid archive are rejected up front
+    assert!(ArchivedData::<Post>::de("AAAA").is_err());
+}
+
+#[tokio::test]
+async fn rkyv_resource_sends_its_data_as_base64() {
+    let Root(_owner, (sc, resource)) = Root::new_with_shared_context(
+        || {
+            let sc = tachy_reaccy::Owner::shared_context().unwrap();
+            (sc, ArcResource::new_rkyv(|| async { post() }))
+        },
+        Some(Arc::new(SsrSharedContext::new())),
+    );
+
+    assert_eq!(resource.clone().await, post());
+
+    let html = sc.pending_data().unwrap().collect::<String>().await;
+    let data = resolved_data(&html, 0);
+    assert_eq!(<Post as SerializableData<Rkyv>>::de(data).unwrap(), post());
+}
@@ -0,0 +1,135 @@
+use futures::StreamExt;
+use tachy_reaccy::shared_context::{SharedContext, SsrSharedContext};
+
+#[tokio::test]
+async fn pending_data_sets_up_globals_once() {
+    let sc = SsrSharedContext::new();
+    let id = sc.next_id();
+    sc.write_async(id, Box::pin(async { String::from("first") }));
+
+    let data = sc.pending_data().unwrap().collect::<Vec<_>>().await;
+    assert_eq!(
+        data,
+        [
+            "__RESOLVED_RESOURCES=[];__PENDING_RESOURCES=[0,];\
+             __RESOURCE_RESOLVERS=[];",
+            "__RESOLVED_RESOURCES[0] = \"first\";\
+             __RESOURCE_RESOLVERS[0]?.();"
+        ]
+    );
+
+    // resources created later are added to the existing globals
+    let id = sc.next_id();
+    sc.write_async(id, Box::pin(async { String::from("second") }));
+    let data = sc.pending_data().unwrap().collect::<Vec<_>>().await;
+    assert_eq!(
+        data,
+        [
+            "__PENDING_RESOURCES.push(1,);",
+            "__RESOLVED_RESOURCES[1] = \"second\";\
+             __RESOURCE_RESOLVERS[1]?.();"
+        ]
+    );
+
+    // nothing new to send
+    let data = sc.pending_data().unwrap().collect::<Vec<_>>().await;
+    assert!(data.is_empty());
+}
+
+#[tokio::test]
+async fn pending_data_cannot_close_script_tag() {
+    let sc = SsrSharedContext::new();
+    let id = sc.next_id();
+    sc.write_async(
+        id,
+        Box::pin(async { String::from("</script><script>alert(1)") }),
+    );
+
+    let data = sc.pending_data().unwrap().collect::<String>().await;
+    assert!(data.ends_with(
+        "__RESOLVED_RESOURCES[0] = \
+         \"\\u003c/script>\\u003cscript>alert(1)\";\
+         __RESOURCE_RESOLVERS[0]?.();"
+    ));
+}
+
+#[cfg(feature = "hydration")]
+#[tokio::test]
+async fn resource_waits_for_streamed_data_instead_of_fetching() {
+    use futures::channel::oneshot;
+    use parking_lot::Mutex;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tachy_reaccy::{
+        async_signal::ArcResource, shared_context::SerializedDataId,
+        PinnedFuture, PinnedStream, Root,
+    };
+
+    // stands in for the browser, where the server's data arrives later
+    #[derive(Debug, Default)]
+    struct Streaming {
+        data: Mutex<Option<oneshot::Receiver<String>>>,
+    }
+
+    impl SharedContext for Streaming {
+        fn next_id(&self) -> SerializedDataId {
+            SerializedDataId::default()
+        }
+
+        fn write_async(
+            &self,
+            _id: SerializedDataId,
+            _fut: PinnedFuture<String>,
+        ) {
+        }
+
+        fn read_data(&self, _id: &SerializedDataId) -> Option<String> {
+            None
+        }
+
+        fn await_data(
+            &self,
+            _id: &SerializedDataId,
+        ) -> Option<PinnedFuture<String>> {
+            let rx = self.data.lock().take()?;
+            Some(Box::pin(async move { rx.await.unwrap() }))
+        }
+
+        fn pending_data(&self) -> Option<PinnedStream<String>> {
+            None
+        }
+
+        fn get_is_hydrating(&self) -> bool {
+            true
+        }
+
+        fn set_is_hydrating(&self, _is_hydrating: bool) {}
+    }
+
+    let (tx, rx) = oneshot::channel();
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let Root(_owner, resource) = Root::new_with_shared_context(
+        {
+            let fetches = Arc::clone(&fetches);
+            move || {
+                ArcResource::new(move || {
+                    let fetches = Arc::clone(&fetches);
+                    async move {
+                        fetches.fetch_add(1, Ordering::Relaxed);
+                        String::from("fetched")
+                    }
+                })
+            }
+        },
+        Some(Arc::new(Streaming {
+            data: Mutex::new(Some(rx)),
+        })),
+    );
+
+    tx.send(String::from("streamed")).unwrap();
+    // awaiting a clone keeps the resource itself alive
+    assert_eq!(resource.clone().await, "streamed");
+    assert_eq!(fetches.load(Ordering::Relaxed), 0);
+}
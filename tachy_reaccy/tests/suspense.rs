@@ -0,0 +1,54 @@
+use futures::channel::oneshot;
+use tachy_reaccy::{
+    async_signal::{ArcAsyncDerived, ScopedFuture, SuspenseContext},
+    context::provide_context,
+    Owner,
+};
+
+#[tokio::test]
+async fn awaiting_async_derived_registers_with_suspense() {
+    let owner = Owner::new();
+    let context = SuspenseContext::new();
+    let (tx, rx) = oneshot::channel::<()>();
+    let (value, waiting) = owner.with(|| {
+        provide_context(context.clone());
+        let rx = parking_lot::Mutex::new(Some(rx));
+        let value = ArcAsyncDerived::new(move || {
+            let rx = rx.lock().take();
+            async move {
+                if let Some(rx) = rx {
+                    _ = rx.await;
+                }
+                42
+            }
+        });
+        let waiting = tokio::spawn(ScopedFuture::new({
+            let value = value.clone();
+            async move { value.await }
+        }));
+        (value, waiting)
+    });
+
+    tokio::task::yield_now().await;
+    assert!(context.pending());
+
+    tx.send(()).unwrap();
+    assert_eq!(waiting.await.unwrap(), 42);
+    assert_eq!(value.await, 42);
+    assert!(!context.pending());
+}
+
+#[test]
+fn tasks_keep_suspense_pending_until_dropped() {
+    let context = SuspenseContext::new();
+    assert!(!context.pending());
+
+    let first = context.task();
+    let second = context.task();
+    assert!(context.pending());
+
+    drop(first);
+    assert!(context.pending());
+    drop(second);
+    assert!(!context.pending());
+}
@@ -1,3 +1,5 @@
+use tachydom::{renderer::Renderer, ssr::StreamBuilder, view::RenderHtml};
+
 /// Indicates which rendering mode should be used for this route during server-side rendering.
 ///
 /// Leptos supports the following ways of rendering HTML that contains `async` data loaded
@@ -30,3 +32,23 @@ pub enum SsrMode {
     InOrder,
     Async,
 }
+
+impl SsrMode {
+    /// Renders the view to a stream of HTML, using this mode.
+    pub fn to_html_stream<View, Rndr>(self, view: View) -> StreamBuilder
+    where
+        View: RenderHtml<Rndr>,
+        Rndr: Renderer,
+        Rndr::Node: Clone,
+        Rndr::Element: Clone,
+    {
+        match self {
+            SsrMode::OutOfOrder => view.to_html_stream_out_of_order(),
+            SsrMode::PartiallyBlocked => {
+                view.to_html_stream_partially_blocked()
+            }
+            SsrMode::InOrder => view.to_html_stream_in_order(),
+            SsrMode::Async => view.to_html_stream_in_order().block_all(),
+        }
+    }
+}
@@ -8,9 +8,18 @@ use crate::{
         Mountable, Position, PositionState, Render, RenderHtml,
     },
 };
-use futures::FutureExt;
+use futures::{
+    future::{abortable, AbortHandle},
+    FutureExt,
+};
 use parking_lot::RwLock;
 use std::{fmt::Debug, future::Future, sync::Arc};
+#[cfg(feature = "reaccy")]
+mod suspense;
+mod transition;
+#[cfg(feature = "reaccy")]
+pub use suspense::*;
+pub use transition::*;
 
 pub trait FutureViewExt: Sized {
     fn suspend(self) -> Suspend<false, (), Self>
@@ -20,6 +29,8 @@ pub trait FutureViewExt: Sized {
         Suspend {
             fallback: (),
             fut: self,
+            should_block: false,
+            transition: None,
         }
     }
 }
@@ -29,6 +40,11 @@ impl<F> FutureViewExt for F where F: Future + Sized {}
 pub struct Suspend<const TRANSITION: bool, Fal, Fut> {
     pub fallback: Fal,
     pub fut: Fut,
+    /// Whether server rendering should wait for this to resolve before
+    /// sending the surrounding HTML.
+    pub should_block: bool,
+    /// The transition this is part of, if any.
+    pub transition: Option<Transition>,
 }
 
 impl<const TRANSITION: bool, Fal, Fut> Suspend<TRANSITION, Fal, Fut> {
@@ -36,13 +52,74 @@ impl<const TRANSITION: bool, Fal, Fut> Suspend<TRANSITION, Fal, Fut> {
         self,
         fallback: Fal2,
     ) -> Suspend<TRANSITION, Fal2, Fut> {
-        let fut = self.fut;
-        Suspend { fallback, fut }
+        let Suspend {
+            fut,
+            should_block,
+            transition,
+            ..
+        } = self;
+        Suspend {
+            fallback,
+            fut,
+            should_block,
+            transition,
+        }
     }
 
+    /// Keeps showing the previous view when this is rebuilt, until the new
+    /// one is ready, instead of showing the fallback.
+    ///
+    /// With the `reaccy` feature, this joins the [`Transition`] provided
+    /// through context, if there is one.
     pub fn transition(self) -> Suspend<true, Fal, Fut> {
-        let Suspend { fallback, fut } = self;
-        Suspend { fallback, fut }
+        let Suspend {
+            fallback,
+            fut,
+            should_block,
+            transition,
+        } = self;
+        #[cfg(feature = "reaccy")]
+        let transition = transition
+            .or_else(tachy_reaccy::context::use_context::<Transition>);
+        Suspend {
+            fallback,
+            fut,
+            should_block,
+            transition,
+        }
+    }
+
+    /// Makes this part of the given [`Transition`], so that it keeps showing
+    /// the previous view when rebuilt, and is swapped in together with the
+    /// other views in the transition.
+    pub fn in_transition(
+        self,
+        transition: &Transition,
+    ) -> Suspend<true, Fal, Fut> {
+        let Suspend {
+            fallback,
+            fut,
+            should_block,
+            ..
+        } = self;
+        Suspend {
+            fallback,
+            fut,
+            should_block,
+            transition: Some(transition.clone()),
+        }
+    }
+
+    /// Marks this as blocking, so that server rendering waits for it to
+    /// resolve before sending any of the HTML around it.
+    ///
+    /// In an out-of-order stream, the fallback is still sent and replaced by
+    /// a script once the content is ready, but the response does not begin
+    /// until then. In a partially-blocked stream, the content is rendered in
+    /// place of the fallback on the server instead.
+    pub fn blocking(mut self) -> Self {
+        self.should_block = true;
+        self
     }
 }
 
@@ -54,16 +131,108 @@ impl<const TRANSITION: bool, Fal, Fut> Debug for Suspend<TRANSITION, Fal, Fut> {
     }
 }
 
-// TODO make this cancelable
+/// The state of a [`Suspend`], which holds the view and the future it is
+/// waiting on, if any.
+///
+/// Only the most recent future is ever rendered: rebuilding the view cancels
+/// the future it was waiting on before, and unmounting or dropping the state
+/// cancels it entirely.
+pub struct SuspendState<Fal, Output, Rndr>
+where
+    Fal: Render<Rndr>,
+    Output: Render<Rndr>,
+    Rndr: Renderer,
+{
+    inner: Arc<RwLock<EitherState<Fal, Output, Rndr>>>,
+    pending: Option<AbortHandle>,
+}
+
+impl<Fal, Output, Rndr> SuspendState<Fal, Output, Rndr>
+where
+    Fal: Render<Rndr>,
+    Output: Render<Rndr>,
+    Rndr: Renderer,
+{
+    fn new(inner: EitherState<Fal, Output, Rndr>) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(inner)),
+            pending: None,
+        }
+    }
+
+    /// Cancels the future this is waiting on, if there is one.
+    fn cancel(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            pending.abort();
+        }
+    }
+}
+
+impl<Fal, Output, Rndr> SuspendState<Fal, Output, Rndr>
+where
+    Fal: Render<Rndr> + 'static,
+    Output: Render<Rndr> + 'static,
+    Rndr: SpawningRenderer + 'static,
+{
+    /// Waits for the future, and renders its output when it resolves, unless
+    /// it is cancelled first.
+    ///
+    /// If this is part of a transition, the output is rendered once every
+    /// view in the transition has resolved.
+    ///
+    /// With the `reaccy` feature, this is registered with the surrounding
+    /// [`SuspenseBoundary`], if any, until the output has been rendered.
+    fn wait_for(
+        &mut self,
+        fut: impl Future<Output = Output> + 'static,
+        transition: Option<TransitionGuard>,
+    ) {
+        self.cancel();
+        // any surrounding suspense boundary waits for this too
+        #[cfg(feature = "reaccy")]
+        let suspense = tachy_reaccy::context::use_context::<
+            tachy_reaccy::async_signal::SuspenseContext,
+        >()
+        .map(|ctx| ctx.task());
+        let (fut, pending) = abortable({
+            let inner = Arc::clone(&self.inner);
+            async move {
+                let value = fut.await;
+                if let Some(transition) = transition {
+                    transition.ready().await;
+                }
+                Either::Right(value).rebuild(&mut *inner.write());
+                #[cfg(feature = "reaccy")]
+                drop(suspense);
+            }
+        });
+        Rndr::Spawn::spawn_local(async move {
+            _ = fut.await;
+        });
+        self.pending = Some(pending);
+    }
+}
+
+impl<Fal, Output, Rndr> Drop for SuspendState<Fal, Output, Rndr>
+where
+    Fal: Render<Rndr>,
+    Output: Render<Rndr>,
+    Rndr: Renderer,
+{
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
 impl<const TRANSITION: bool, Fal, Fut, Rndr> Render<Rndr>
     for Suspend<TRANSITION, Fal, Fut>
 where
     Fal: Render<Rndr> + 'static,
     Fut: Future + 'static,
-    Fut::Output: Render<Rndr>,
+    Fut::Output: Render<Rndr> + 'static,
     Rndr: SpawningRenderer + 'static,
 {
-    type State = Arc<RwLock<EitherState<Fal, Fut::Output, Rndr>>>;
+    type State = SuspendState<Fal, Fut::Output, Rndr>;
 
     fn build(self) -> Self::State {
         // poll the future once immediately
@@ -80,38 +249,37 @@ where
         let initially_pending = matches!(initial, Either::Left(_));
 
         // now we can build the initial state
-        let state = Arc::new(RwLock::new(initial.build()));
+        let mut state = SuspendState::new(initial.build());
 
         // if the initial state was pending, spawn a future to wait for it
         // spawning immediately means that our now_or_never poll result isn't lost
         // if it wasn't pending at first, we don't need to poll the Future again
         if initially_pending {
-            Rndr::Spawn::spawn_local({
-                let state = Arc::clone(&state);
-                async move {
-                    let value = fut.as_mut().await;
-                    Either::Right(value).rebuild(&mut *state.write());
-                }
-            });
+            state.wait_for(fut, None);
         }
 
         state
     }
 
     fn rebuild(self, state: &mut Self::State) {
+        // whatever we were waiting for before is now out of date
+        state.cancel();
+
         if !TRANSITION {
             // fall back to fallback state
-            Either::Left(self.fallback).rebuild(&mut *state.write());
+            Either::Left(self.fallback).rebuild(&mut *state.inner.write());
         }
 
+        // in a transition, the previous view stays until the whole
+        // transition is ready
+        let transition = if TRANSITION {
+            self.transition.as_ref().map(Transition::start)
+        } else {
+            None
+        };
+
         // spawn the future, and rebuild the state when it resolves
-        Rndr::Spawn::spawn_local({
-            let state = Arc::clone(state);
-            async move {
-                let value = self.fut.await;
-                Either::Right(value).rebuild(&mut *state.write());
-            }
-        });
+        state.wait_for(self.fut, transition);
     }
 }
 
@@ -120,7 +288,7 @@ impl<const TRANSITION: bool, Fal, Fut, Rndr> RenderHtml<Rndr>
 where
     Fal: RenderHtml<Rndr> + Send + Sync + 'static,
     Fut: Future + Send + Sync + 'static,
-    Fut::Output: RenderHtml<Rndr>,
+    Fut::Output: RenderHtml<Rndr> + 'static,
     Rndr: SpawningRenderer + 'static,
     Rndr::Node: Clone,
     Rndr::Element: Clone,
@@ -141,6 +309,7 @@ where
     {
         buf.next_id();
 
+        let should_block = self.should_block;
         let mut fut = Box::pin(self.fut);
         match fut.as_mut().now_or_never() {
             Some(resolved) => {
@@ -148,32 +317,32 @@ where
                     .to_html_async_with_buf::<OUT_OF_ORDER>(buf, position);
             }
             None => {
-                let id = buf.clone_id();
-
                 // out-of-order streams immediately push fallback,
-                // wrapped by suspense markers
-                if OUT_OF_ORDER {
+                // wrapped by suspense markers, unless a partially-blocked
+                // stream is going to render this in place
+                if OUT_OF_ORDER && !(should_block && buf.is_partially_blocked())
+                {
+                    // the fragment replaces the fallback, so it starts in the
+                    // same position
+                    let mut fragment_position = *position;
                     buf.push_fallback(self.fallback, position);
                     buf.push_async_out_of_order(
-                        false, /* TODO should_block */ fut, position,
+                        should_block,
+                        fut,
+                        &mut fragment_position,
                     );
                 } else {
-                    buf.push_async(
-                        false, // TODO should_block
-                        {
-                            let mut position = *position;
-                            async move {
-                                let value = fut.await;
-                                let mut builder = StreamBuilder::new(id);
-                                Either::<Fal, Fut::Output>::Right(value)
-                                    .to_html_async_with_buf::<OUT_OF_ORDER>(
-                                    &mut builder,
-                                    &mut position,
-                                );
-                                builder.finish().take_chunks()
-                            }
-                        },
-                    );
+                    let mut builder = buf.child_builder();
+                    let mut child_position = *position;
+                    buf.push_async(should_block, async move {
+                        let value = fut.await;
+                        Either::<Fal, Fut::Output>::Right(value)
+                            .to_html_async_with_buf::<OUT_OF_ORDER>(
+                                &mut builder,
+                                &mut child_position,
+                            );
+                        builder.finish().take_chunks()
+                    });
                     *position = Position::NextChild;
                 }
             }
@@ -199,29 +368,21 @@ where
         let initially_pending = matches!(initial, Either::Left(_));
 
         // now we can build the initial state
-        let state = Arc::new(RwLock::new(
-            initial.hydrate::<FROM_SERVER>(cursor, position),
-        ));
+        let mut state =
+            SuspendState::new(initial.hydrate::<FROM_SERVER>(cursor, position));
 
         // if the initial state was pending, spawn a future to wait for it
         // spawning immediately means that our now_or_never poll result isn't lost
         // if it wasn't pending at first, we don't need to poll the Future again
         if initially_pending {
-            Rndr::Spawn::spawn_local({
-                let state = Arc::clone(&state);
-                async move {
-                    let value = fut.as_mut().await;
-                    Either::Right(value).rebuild(&mut *state.write());
-                }
-            });
+            state.wait_for(fut, None);
         }
 
         state
     }
 }
 
-impl<Rndr, Fal, Output> Mountable<Rndr>
-    for Arc<RwLock<EitherState<Fal, Output, Rndr>>>
+impl<Rndr, Fal, Output> Mountable<Rndr> for SuspendState<Fal, Output, Rndr>
 where
     Fal: Render<Rndr>,
     Fal::State: Mountable<Rndr>,
@@ -230,7 +391,9 @@ where
     Rndr: Renderer,
 {
     fn unmount(&mut self) {
-        self.write().unmount();
+        // a view that has been removed doesn't need its data any more
+        self.cancel();
+        self.inner.write().unmount();
     }
 
     fn mount(
@@ -238,7 +401,7 @@ where
         parent: &<Rndr as Renderer>::Element,
         marker: Option<&<Rndr as Renderer>::Node>,
     ) {
-        self.write().mount(parent, marker);
+        self.inner.write().mount(parent, marker);
     }
 
     fn insert_before_this(
@@ -246,6 +409,192 @@ where
         parent: &<Rndr as Renderer>::Element,
         child: &mut dyn Mountable<Rndr>,
     ) -> bool {
-        self.write().insert_before_this(parent, child)
+        self.inner.write().insert_before_this(parent, child)
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use super::{FutureViewExt, Transition};
+    use crate::{
+        renderer::mock_dom::MockDom,
+        view::{Mountable, Render},
+    };
+    use futures::channel::oneshot;
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    #[tokio::test]
+    async fn rebuilding_cancels_the_previous_future() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let body = MockDom::load_html("");
+                let (first_tx, first_rx) = oneshot::channel::<&str>();
+                let (second_tx, second_rx) = oneshot::channel::<&str>();
+
+                let mut state = Render::<MockDom>::build(
+                    async { first_rx.await.unwrap() }
+                        .suspend()
+                        .with_fallback("Loading"),
+                );
+                state.mount(&body, None);
+                async { second_rx.await.unwrap() }
+                    .suspend()
+                    .with_fallback("Loading")
+                    .rebuild(&mut state);
+
+                second_tx.send("Second").unwrap();
+                tokio::task::yield_now().await;
+                assert_eq!(body.to_debug_html(), "<body>Second<!></body>");
+
+                // the first future was cancelled, so it can't overwrite it
+                _ = first_tx.send("First");
+                tokio::task::yield_now().await;
+                assert_eq!(body.to_debug_html(), "<body>Second<!></body>");
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn unmounting_cancels_the_future() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let body = MockDom::load_html("");
+                let (tx, rx) = oneshot::channel::<()>();
+                let resolved = Arc::new(AtomicBool::new(false));
+
+                let mut state = Render::<MockDom>::build(
+                    {
+                        let resolved = Arc::clone(&resolved);
+                        async move {
+                            rx.await.unwrap();
+                            resolved.store(true, Ordering::Relaxed);
+                            "Resolved"
+                        }
+                    }
+                    .suspend()
+                    .with_fallback("Loading"),
+                );
+                state.mount(&body, None);
+                assert_eq!(body.to_debug_html(), "<body>Loading<!></body>");
+
+                state.unmount();
+                _ = tx.send(());
+                tokio::task::yield_now().await;
+                assert!(!resolved.load(Ordering::Relaxed));
+                assert_eq!(body.to_debug_html(), "<body></body>");
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn transition_keeps_the_previous_view_until_ready() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let body = MockDom::load_html("");
+                let transition = Transition::new();
+                let (tx, rx) = oneshot::channel::<&str>();
+
+                let mut state = Render::<MockDom>::build(
+                    async { "First" }
+                        .suspend()
+                        .with_fallback("Loading")
+                        .in_transition(&transition),
+                );
+                state.mount(&body, None);
+                assert!(!transition.is_pending());
+
+                async { rx.await.unwrap() }
+                    .suspend()
+                    .with_fallback("Loading")
+                    .in_transition(&transition)
+                    .rebuild(&mut state);
+                assert!(transition.is_pending());
+                assert_eq!(body.to_debug_html(), "<body>First<!></body>");
+
+                tx.send("Second").unwrap();
+                tokio::task::yield_now().await;
+                assert!(!transition.is_pending());
+                assert_eq!(body.to_debug_html(), "<body>Second<!></body>");
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn views_in_a_transition_are_swapped_in_together() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let body = MockDom::load_html("");
+                let transition = Transition::new();
+                let (a_tx, a_rx) = oneshot::channel::<&str>();
+                let (b_tx, b_rx) = oneshot::channel::<&str>();
+
+                let view = |value: &'static str| {
+                    async move { value }
+                        .suspend()
+                        .with_fallback("Loading")
+                        .in_transition(&transition)
+                };
+                let mut a = Render::<MockDom>::build(view("A1"));
+                a.mount(&body, None);
+                let mut b = Render::<MockDom>::build(view("B1"));
+                b.mount(&body, None);
+
+                async { a_rx.await.unwrap() }
+                    .suspend()
+                    .with_fallback("Loading")
+                    .in_transition(&transition)
+                    .rebuild(&mut a);
+                async { b_rx.await.unwrap() }
+                    .suspend()
+                    .with_fallback("Loading")
+                    .in_transition(&transition)
+                    .rebuild(&mut b);
+
+                // the first view is ready, but waits for the second
+                a_tx.send("A2").unwrap();
+                tokio::task::yield_now().await;
+                assert!(transition.is_pending());
+                assert_eq!(body.to_debug_html(), "<body>A1<!>B1<!></body>");
+
+                b_tx.send("B2").unwrap();
+                tokio::task::yield_now().await;
+                tokio::task::yield_now().await;
+                assert!(!transition.is_pending());
+                assert_eq!(body.to_debug_html(), "<body>A2<!>B2<!></body>");
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn cancelled_views_leave_the_transition() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let body = MockDom::load_html("");
+                let transition = Transition::new();
+                let (_tx, rx) = oneshot::channel::<&str>();
+
+                let mut state = Render::<MockDom>::build(
+                    async { "First" }
+                        .suspend()
+                        .with_fallback("Loading")
+                        .in_transition(&transition),
+                );
+                state.mount(&body, None);
+                async { rx.await.unwrap() }
+                    .suspend()
+                    .with_fallback("Loading")
+                    .in_transition(&transition)
+                    .rebuild(&mut state);
+                assert!(transition.is_pending());
+
+                // the cancelled future is dropped the next time it's polled
+                state.unmount();
+                tokio::task::yield_now().await;
+                assert!(!transition.is_pending());
+            })
+            .await;
     }
 }
@@ -0,0 +1,499 @@
+use crate::{
+    html::element::{CreateElement, Div},
+    hydration::Cursor,
+    renderer::{CastFrom, Renderer},
+    ssr::StreamBuilder,
+    view::{Mountable, Position, PositionState, Render, RenderHtml},
+};
+use futures::StreamExt;
+use parking_lot::RwLock;
+use std::sync::Arc;
+use tachy_reaccy::{
+    async_signal::SuspenseContext, context::provide_context,
+    render_effect::RenderEffect, Owner,
+};
+
+/// Groups all of the async work beneath it under a single fallback.
+///
+/// Every [`Suspend`](super::Suspend) in its children, and every `.await` on
+/// a resource or async derived signal while they render, is registered with
+/// the boundary through a [`SuspenseContext`]. The fallback is shown until
+/// all of them are ready, and then the children are swapped in at once.
+///
+/// When streaming out of order, the whole group is sent as a single fragment,
+/// with one pair of markers around the fallback.
+pub struct SuspenseBoundary<Fal, Chil> {
+    owner: Owner,
+    context: SuspenseContext,
+    fallback: Fal,
+    children: Chil,
+}
+
+/// Creates a [`SuspenseBoundary`] around the view returned by `children`,
+/// which shows `fallback` while anything inside it is pending.
+///
+/// The children are created inside the boundary, so that the async work they
+/// start is tracked by it.
+pub fn suspense<Fal, Chil>(
+    fallback: Fal,
+    children: impl FnOnce() -> Chil,
+) -> SuspenseBoundary<Fal, Chil> {
+    let owner = Owner::new();
+    let context = SuspenseContext::new();
+    let children = owner.with(|| {
+        provide_context(context.clone());
+        children()
+    });
+    SuspenseBoundary {
+        owner,
+        context,
+        fallback,
+        children,
+    }
+}
+
+/// The state of a [`SuspenseBoundary`].
+///
+/// Both the fallback and the children are kept alive, so that the children
+/// can keep loading while they are hidden. Whichever one is hidden is moved
+/// into a detached element.
+pub struct SuspenseBoundaryState<Fal, Chil, Rndr>
+where
+    Rndr: Renderer,
+{
+    inner: Arc<RwLock<BoundaryInner<Fal, Chil, Rndr>>>,
+    // swaps between the fallback and the children
+    _effect: RenderEffect<()>,
+    _owner: Owner,
+}
+
+struct BoundaryInner<Fal, Chil, Rndr>
+where
+    Rndr: Renderer,
+{
+    fallback: Fal,
+    children: Chil,
+    showing_fallback: bool,
+    marker: Rndr::Placeholder,
+    hidden: Rndr::Element,
+}
+
+impl<Fal, Chil, Rndr> BoundaryInner<Fal, Chil, Rndr>
+where
+    Fal: Mountable<Rndr>,
+    Chil: Mountable<Rndr>,
+    Rndr: Renderer,
+{
+    fn show_fallback(&mut self, pending: bool) {
+        if pending == self.showing_fallback {
+            return;
+        }
+        self.showing_fallback = pending;
+
+        // if the boundary isn't mounted yet, the view that is now visible
+        // waits in the hidden element until it is
+        let parent = Rndr::get_parent(self.marker.as_ref())
+            .and_then(Rndr::Element::cast_from);
+        let marker = Some(self.marker.as_ref());
+        if pending {
+            self.children.mount(&self.hidden, None);
+            if let Some(parent) = parent {
+                self.fallback.mount(&parent, marker);
+            }
+        } else {
+            self.fallback.mount(&self.hidden, None);
+            if let Some(parent) = parent {
+                self.children.mount(&parent, marker);
+            }
+        }
+    }
+}
+
+impl<Fal, Chil, Rndr> SuspenseBoundaryState<Fal, Chil, Rndr>
+where
+    Fal: Mountable<Rndr> + 'static,
+    Chil: Mountable<Rndr> + 'static,
+    Rndr: Renderer + 'static,
+    Div: CreateElement<Rndr>,
+{
+    fn new(
+        owner: Owner,
+        context: SuspenseContext,
+        mut fallback: Fal,
+        children: Chil,
+        marker: Rndr::Placeholder,
+    ) -> Self {
+        let hidden = Rndr::create_element(Div);
+        fallback.mount(&hidden, None);
+        let inner = Arc::new(RwLock::new(BoundaryInner {
+            fallback,
+            children,
+            showing_fallback: false,
+            marker,
+            hidden,
+        }));
+        Self {
+            _effect: watch(context, &inner),
+            inner,
+            _owner: owner,
+        }
+    }
+}
+
+fn watch<Fal, Chil, Rndr>(
+    context: SuspenseContext,
+    inner: &Arc<RwLock<BoundaryInner<Fal, Chil, Rndr>>>,
+) -> RenderEffect<()>
+where
+    Fal: Mountable<Rndr> + 'static,
+    Chil: Mountable<Rndr> + 'static,
+    Rndr: Renderer + 'static,
+{
+    let inner = Arc::clone(inner);
+    RenderEffect::new(move |_| {
+        let pending = context.pending();
+        inner.write().show_fallback(pending);
+    })
+}
+
+impl<Fal, Chil, Rndr> Render<Rndr> for SuspenseBoundary<Fal, Chil>
+where
+    Fal: Render<Rndr>,
+    Fal::State: 'static,
+    Chil: Render<Rndr>,
+    Chil::State: 'static,
+    Rndr: Renderer + 'static,
+    Div: CreateElement<Rndr>,
+{
+    type State = SuspenseBoundaryState<Fal::State, Chil::State, Rndr>;
+
+    fn build(self) -> Self::State {
+        let SuspenseBoundary {
+            owner,
+            context,
+            fallback,
+            children,
+        } = self;
+        owner.clone().with(|| {
+            let children = children.build();
+            let fallback = fallback.build();
+            SuspenseBoundaryState::new(
+                owner,
+                context,
+                fallback,
+                children,
+                Rndr::create_placeholder(),
+            )
+        })
+    }
+
+    fn rebuild(self, state: &mut Self::State) {
+        let SuspenseBoundary {
+            owner,
+            context,
+            fallback,
+            children,
+        } = self;
+        owner.with(|| {
+            {
+                let mut inner = state.inner.write();
+                children.rebuild(&mut inner.children);
+                fallback.rebuild(&mut inner.fallback);
+            }
+            // the new children report to the new boundary's context
+            state._effect = watch(context, &state.inner);
+        });
+        state._owner = owner;
+    }
+}
+
+impl<Fal, Chil, Rndr> RenderHtml<Rndr> for SuspenseBoundary<Fal, Chil>
+where
+    Fal: RenderHtml<Rndr>,
+    Fal::State: 'static,
+    Chil: RenderHtml<Rndr>,
+    Chil::State: 'static,
+    Rndr: Renderer + 'static,
+    Rndr::Node: Clone,
+    Rndr::Element: Clone,
+    Div: CreateElement<Rndr>,
+{
+    const MIN_LENGTH: usize = Fal::MIN_LENGTH;
+
+    fn to_html_with_buf(self, buf: &mut String, position: &mut Position) {
+        // without streaming, nothing inside can be waited for, so the children
+        // are rendered as they are, with anything suspended inside them showing
+        // its own fallback; this is what `hydrate` expects to find, and the
+        // boundary then shows its fallback on the client if it's still pending
+        let SuspenseBoundary {
+            owner, children, ..
+        } = self;
+        owner.with(|| children.to_html_with_buf(buf, position));
+        buf.push_str("<!>");
+        *position = Position::NextChild;
+    }
+
+    fn to_html_async_with_buf<const OUT_OF_ORDER: bool>(
+        self,
+        buf: &mut StreamBuilder,
+        position: &mut Position,
+    ) where
+        Self: Sized,
+    {
+        buf.next_id();
+
+        // the children are rendered in order, so that anything suspended
+        // inside them is part of this group rather than its own fragment
+        let SuspenseBoundary {
+            owner,
+            fallback,
+            children,
+            ..
+        } = self;
+        let mut children_buf = buf.child_builder();
+        let mut children_position = *position;
+        owner.with(|| {
+            children.to_html_async_with_buf::<false>(
+                &mut children_buf,
+                &mut children_position,
+            )
+        });
+
+        // the group blocks if anything in it does, and is rendered in place
+        // if a partially-blocked stream is going to wait for it anyway
+        let should_block = children_buf.is_blocking();
+        match children_buf.ready_html() {
+            Some(html) => buf.push_sync(&html),
+            None if OUT_OF_ORDER
+                && !(should_block && buf.is_partially_blocked()) =>
+            {
+                buf.push_fallback(fallback, position);
+                buf.push_resolved_out_of_order(
+                    should_block,
+                    children_buf.collect::<String>(),
+                );
+                return;
+            }
+            None => buf.push_async(should_block, async move {
+                children_buf.finish().take_chunks()
+            }),
+        }
+        buf.push_sync("<!>");
+        *position = Position::NextChild;
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<Rndr>,
+        position: &PositionState,
+    ) -> Self::State {
+        let SuspenseBoundary {
+            owner,
+            context,
+            fallback,
+            children,
+        } = self;
+        owner.clone().with(|| {
+            let children = children.hydrate::<FROM_SERVER>(cursor, position);
+            cursor.sibling();
+            let marker = cursor.expect_placeholder();
+            position.set(Position::NextChild);
+            let fallback = fallback.build();
+            SuspenseBoundaryState::new(
+                owner, context, fallback, children, marker,
+            )
+        })
+    }
+}
+
+impl<Fal, Chil, Rndr> Mountable<Rndr> for SuspenseBoundaryState<Fal, Chil, Rndr>
+where
+    Fal: Mountable<Rndr>,
+    Chil: Mountable<Rndr>,
+    Rndr: Renderer,
+{
+    fn unmount(&mut self) {
+        let mut inner = self.inner.write();
+        inner.children.unmount();
+        inner.fallback.unmount();
+        inner.marker.unmount();
+    }
+
+    fn mount(
+        &mut self,
+        parent: &<Rndr as Renderer>::Element,
+        marker: Option<&<Rndr as Renderer>::Node>,
+    ) {
+        let mut inner = self.inner.write();
+        let inner = &mut *inner;
+        inner.marker.mount(parent, marker);
+        let marker = Some(inner.marker.as_ref());
+        if inner.showing_fallback {
+            inner.fallback.mount(parent, marker);
+        } else {
+            inner.children.mount(parent, marker);
+        }
+    }
+
+    fn insert_before_this(
+        &self,
+        parent: &<Rndr as Renderer>::Element,
+        child: &mut dyn Mountable<Rndr>,
+    ) -> bool {
+        let inner = self.inner.read();
+        let inserted = if inner.showing_fallback {
+            inner.fallback.insert_before_this(parent, child)
+        } else {
+            inner.children.insert_before_this(parent, child)
+        };
+        inserted || inner.marker.insert_before_this(parent, child)
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use super::suspense;
+    use crate::{
+        async_views::FutureViewExt,
+        html::element::{p, ElementChild, HtmlElement, P},
+        hydration::{set_mismatch_behavior, MismatchBehavior},
+        renderer::{dom::Dom, mock_dom::MockDom},
+        view::{Mountable, Render, RenderHtml},
+    };
+    use futures::{channel::oneshot, StreamExt};
+    use std::time::Duration;
+    use tokio::time::sleep;
+
+    #[tokio::test]
+    async fn shows_one_fallback_until_everything_is_ready() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let body = MockDom::load_html("");
+                let (a_tx, a_rx) = oneshot::channel::<&str>();
+                let (b_tx, b_rx) = oneshot::channel::<&str>();
+
+                let view = suspense("Loading", move || {
+                    (
+                        async { a_rx.await.unwrap() }.suspend(),
+                        async { b_rx.await.unwrap() }.suspend(),
+                    )
+                });
+                let mut state = Render::<MockDom>::build(view);
+                state.mount(&body, None);
+                assert_eq!(body.to_debug_html(), "<body>Loading<!></body>");
+
+                // the first is ready, but the group waits for the second
+                a_tx.send("A").unwrap();
+                tokio::task::yield_now().await;
+                tokio::task::yield_now().await;
+                assert_eq!(body.to_debug_html(), "<body>Loading<!></body>");
+
+                b_tx.send("B").unwrap();
+                tokio::task::yield_now().await;
+                tokio::task::yield_now().await;
+                assert_eq!(body.to_debug_html(), "<body>A<!>B<!><!></body>");
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn shows_children_when_nothing_is_pending() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let body = MockDom::load_html("");
+                let view = suspense("Loading", || async { "Ready" }.suspend());
+                let mut state = Render::<MockDom>::build(view);
+                state.mount(&body, None);
+                assert_eq!(body.to_debug_html(), "<body>Ready<!><!></body>");
+            })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn in_order_stream_waits_for_the_group() {
+        let el: HtmlElement<P, _, _, Dom> =
+            p().child(suspense("Loading", || {
+                (
+                    async {
+                        sleep(Duration::from_millis(10)).await;
+                        "A"
+                    }
+                    .suspend(),
+                    async {
+                        sleep(Duration::from_millis(20)).await;
+                        "B"
+                    }
+                    .suspend(),
+                )
+            }));
+        let html = el.to_html_stream_in_order().collect::<String>().await;
+        assert_eq!(html, "<p>A<!>B<!><!></p>");
+    }
+
+    #[tokio::test]
+    async fn out_of_order_stream_sends_the_group_as_one_fragment() {
+        let el: HtmlElement<P, _, _, Dom> =
+            p().child(suspense("Loading", || {
+                (
+                    async {
+                        sleep(Duration::from_millis(10)).await;
+                        "A"
+                    }
+                    .suspend(),
+                    async {
+                        sleep(Duration::from_millis(20)).await;
+                        "B"
+                    }
+                    .suspend(),
+                )
+            }));
+        let mut stream = el.to_html_stream_out_of_order();
+        assert_eq!(
+            stream.next().await.unwrap(),
+            "<p><!--s-1-o-->Loading<!--s-1-c--></p>"
+        );
+        let fragment = stream.collect::<String>().await;
+        assert!(fragment.contains(
+            "<template id=\"1-f\">A<!>B<!><!></template><script>\
+             __tachys_swap(\"1-\")</script>"
+        ));
+        assert_eq!(fragment.matches("<template id=").count(), 1);
+    }
+
+    #[tokio::test]
+    async fn partially_blocked_stream_renders_blocking_group_in_place() {
+        let el: HtmlElement<P, _, _, Dom> =
+            p().child(suspense("Loading", || {
+                async {
+                    sleep(Duration::from_millis(10)).await;
+                    "Blocking"
+                }
+                .suspend()
+                .blocking()
+            }));
+        let mut stream = el.to_html_stream_partially_blocked();
+        assert_eq!(stream.next().await.unwrap(), "<p>Blocking<!><!></p>");
+        assert_eq!(stream.next().await, None);
+    }
+
+    #[tokio::test]
+    async fn hydrates_the_html_rendered_without_streaming() {
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let view = || suspense("Loading", || p().child("Ready"));
+                let html = RenderHtml::<MockDom>::to_html(view());
+                assert_eq!(html, "<p>Ready</p><!>");
+
+                let body = MockDom::load_html(&html);
+                set_mismatch_behavior(MismatchBehavior::Panic);
+                let mut state = view().hydrate_from::<true>(&body);
+                tokio::task::yield_now().await;
+                assert_eq!(
+                    body.to_debug_html(),
+                    "<body><p>Ready</p><!></body>"
+                );
+                state.unmount();
+            })
+            .await;
+    }
+}
@@ -0,0 +1,152 @@
+use futures::channel::oneshot;
+use parking_lot::Mutex;
+use std::sync::Arc;
+#[cfg(feature = "reaccy")]
+use tachy_reaccy::{
+    signal::ArcRwSignal,
+    signal_traits::{SignalGet, SignalSet},
+};
+
+/// Groups together the [`Suspend`](super::Suspend)s that are updated as part
+/// of the same transition.
+///
+/// While a transition is pending, each of its suspended views keeps showing
+/// its previous content instead of its fallback. Views that resolve early
+/// wait for the rest, so that the whole group is swapped in at once.
+///
+/// A suspend joins a transition with [`Suspend::in_transition`], or, if the
+/// transition has been provided through context, with
+/// [`Suspend::transition`].
+///
+/// [`Suspend::in_transition`]: super::Suspend::in_transition
+/// [`Suspend::transition`]: super::Suspend::transition
+#[derive(Clone)]
+pub struct Transition {
+    inner: Arc<Mutex<TransitionInner>>,
+    #[cfg(feature = "reaccy")]
+    is_pending: ArcRwSignal<bool>,
+}
+
+#[derive(Default)]
+struct TransitionInner {
+    pending: usize,
+    waiting: Vec<oneshot::Sender<()>>,
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+            #[cfg(feature = "reaccy")]
+            is_pending: ArcRwSignal::new(false),
+        }
+    }
+}
+
+impl std::fmt::Debug for Transition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transition")
+            .field("pending", &self.inner.lock().pending)
+            .finish()
+    }
+}
+
+impl Transition {
+    /// Creates a new transition.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether any of the views in this transition are still waiting for
+    /// their new content.
+    ///
+    /// With the `reaccy` feature, this is reactive, so it can be used to show
+    /// a loading indicator while the old content is still on screen.
+    pub fn is_pending(&self) -> bool {
+        #[cfg(feature = "reaccy")]
+        {
+            self.is_pending.get()
+        }
+        #[cfg(not(feature = "reaccy"))]
+        {
+            self.inner.lock().pending > 0
+        }
+    }
+
+    /// Registers a view that has started waiting for new content.
+    pub(crate) fn start(&self) -> TransitionGuard {
+        let was_idle = {
+            let mut inner = self.inner.lock();
+            inner.pending += 1;
+            inner.pending == 1
+        };
+        if was_idle {
+            self.set_pending(true);
+        }
+        TransitionGuard {
+            transition: self.clone(),
+            done: false,
+        }
+    }
+
+    // removes a view from the pending views; if it was the last one, wakes
+    // all the others and returns `true`, otherwise adds `waker` to the views
+    // waiting for the rest
+    fn finish_one(&self, waker: Option<oneshot::Sender<()>>) -> bool {
+        let waiting = {
+            let mut inner = self.inner.lock();
+            inner.pending -= 1;
+            if inner.pending == 0 {
+                Some(std::mem::take(&mut inner.waiting))
+            } else {
+                inner.waiting.extend(waker);
+                None
+            }
+        };
+        match waiting {
+            Some(waiting) => {
+                self.set_pending(false);
+                for view in waiting {
+                    _ = view.send(());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn set_pending(&self, _pending: bool) {
+        #[cfg(feature = "reaccy")]
+        self.is_pending.set(_pending);
+    }
+}
+
+/// A view's place in a [`Transition`], which it holds while waiting for its
+/// new content.
+///
+/// If the view stops waiting without finishing, for example because its
+/// future was cancelled, dropping the guard removes it from the transition.
+pub(crate) struct TransitionGuard {
+    transition: Transition,
+    done: bool,
+}
+
+impl TransitionGuard {
+    /// Marks this view as ready, and waits until every other view in the
+    /// transition is ready too.
+    pub async fn ready(mut self) {
+        self.done = true;
+        let (tx, rx) = oneshot::channel();
+        if !self.transition.finish_one(Some(tx)) {
+            _ = rx.await;
+        }
+    }
+}
+
+impl Drop for TransitionGuard {
+    fn drop(&mut self) {
+        if !self.done {
+            self.transition.finish_one(None);
+        }
+    }
+}
@@ -0,0 +1,487 @@
+use crate::{
+    html::{
+        attribute::{escape_attr, Attribute},
+        event::{on, EventDescriptor, On},
+    },
+    renderer::{DomRenderer, Renderer},
+    view::{Position, ToTemplate},
+};
+use std::{borrow::Cow, fmt::Debug};
+
+/// A set of attributes and event listeners that is only known at runtime.
+///
+/// This can be spread onto any element with `{..attrs}` in the `view` macro,
+/// which allows a wrapper component to pass arbitrary attributes through to
+/// the element it renders.
+///
+/// A `class` or `style` entry is merged with any other classes or styles on
+/// the element, rather than replacing them. Attributes whose names could not
+/// be written into HTML, because they contain whitespace, quotes, `/`, `>` or
+/// `=`, are ignored.
+pub struct DynAttrs<R: DomRenderer>(Vec<DynAttr<R>>);
+
+/// A single entry in [`DynAttrs`].
+pub enum DynAttr<R: DomRenderer> {
+    /// An attribute, with its name and value.
+    Attr(Cow<'static, str>, DynAttrValue),
+    /// An event listener.
+    Event(On<R>),
+}
+
+/// The value of an attribute in [`DynAttrs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DynAttrValue {
+    /// An attribute with a string value.
+    Str(Cow<'static, str>),
+    /// A boolean attribute, which is present if `true` and absent if `false`.
+    Bool(bool),
+}
+
+impl<R: DomRenderer> DynAttrs<R> {
+    /// Creates an empty set of attributes.
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Adds an attribute.
+    pub fn attr(
+        mut self,
+        key: impl Into<Cow<'static, str>>,
+        value: impl Into<DynAttrValue>,
+    ) -> Self {
+        self.0.push(DynAttr::Attr(key.into(), value.into()));
+        self
+    }
+
+    /// Adds an event listener.
+    pub fn on<E>(
+        mut self,
+        event: E,
+        cb: impl FnMut(E::EventType) + 'static,
+    ) -> Self
+    where
+        E: EventDescriptor + 'static,
+        E::EventType: 'static,
+        E::EventType: From<R::Event>,
+    {
+        self.0.push(DynAttr::Event(on(event, cb)));
+        self
+    }
+
+    /// Adds an entry.
+    pub fn push(&mut self, attr: DynAttr<R>) {
+        self.0.push(attr);
+    }
+
+    /// Whether there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<R: DomRenderer> Default for DynAttrs<R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: DomRenderer> Debug for DynAttrs<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(&self.0).finish()
+    }
+}
+
+impl<R: DomRenderer> Debug for DynAttr<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Attr(key, value) => {
+                f.debug_tuple("Attr").field(key).field(value).finish()
+            }
+            Self::Event(event) => f.debug_tuple("Event").field(event).finish(),
+        }
+    }
+}
+
+impl<R: DomRenderer> From<Vec<DynAttr<R>>> for DynAttrs<R> {
+    fn from(value: Vec<DynAttr<R>>) -> Self {
+        Self(value)
+    }
+}
+
+impl<R: DomRenderer> FromIterator<DynAttr<R>> for DynAttrs<R> {
+    fn from_iter<T: IntoIterator<Item = DynAttr<R>>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<R: DomRenderer> Extend<DynAttr<R>> for DynAttrs<R> {
+    fn extend<T: IntoIterator<Item = DynAttr<R>>>(&mut self, iter: T) {
+        self.0.extend(iter);
+    }
+}
+
+impl<R: DomRenderer> IntoIterator for DynAttrs<R> {
+    type Item = DynAttr<R>;
+    type IntoIter = std::vec::IntoIter<DynAttr<R>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl From<&'static str> for DynAttrValue {
+    fn from(value: &'static str) -> Self {
+        Self::Str(value.into())
+    }
+}
+
+impl From<String> for DynAttrValue {
+    fn from(value: String) -> Self {
+        Self::Str(value.into())
+    }
+}
+
+impl From<Cow<'static, str>> for DynAttrValue {
+    fn from(value: Cow<'static, str>) -> Self {
+        Self::Str(value)
+    }
+}
+
+impl From<bool> for DynAttrValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl<T> From<Option<T>> for DynAttrValue
+where
+    T: Into<DynAttrValue>,
+{
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(Self::Bool(false))
+    }
+}
+
+// attribute names can't contain any of these characters in HTML, so other
+// attributes could be injected through them
+fn is_valid_attr_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.chars().any(|ch| {
+            ch.is_whitespace()
+                || ch.is_control()
+                || matches!(ch, '"' | '\'' | '/' | '>' | '=')
+        })
+}
+
+// the declarations in an inline style, as `(property, value)` pairs
+fn style_properties(style: &str) -> impl Iterator<Item = (&str, &str)> {
+    style.split(';').filter_map(|decl| {
+        let (name, value) = decl.split_once(':')?;
+        Some((name.trim(), value.trim()))
+    })
+}
+
+// updates an attribute from its previous to its new value; the classes and
+// styles in `class` and `style` are added to and removed from the element's
+// own, as they are merged with them on the server
+fn update_attr<R: DomRenderer>(
+    el: &R::Element,
+    key: &str,
+    prev: Option<&str>,
+    value: Option<&str>,
+) {
+    match key {
+        "class" => {
+            let list = R::class_list(el);
+            let value = value.unwrap_or_default();
+            for class in prev.unwrap_or_default().split_whitespace() {
+                if !value.split_whitespace().any(|new| new == class) {
+                    R::remove_class(&list, class);
+                }
+            }
+            for class in value.split_whitespace() {
+                R::add_class(&list, class);
+            }
+        }
+        "style" => {
+            let style = R::style(el);
+            let value = value.unwrap_or_default();
+            for (name, _) in style_properties(prev.unwrap_or_default()) {
+                if !style_properties(value).any(|(new, _)| new == name) {
+                    R::set_css_property(&style, name, "");
+                }
+            }
+            for (name, value) in style_properties(value) {
+                R::set_css_property(&style, name, value);
+            }
+        }
+        _ => match value {
+            Some(value) => R::set_attribute(el, key, value),
+            None => R::remove_attribute(el, key),
+        },
+    }
+}
+
+impl DynAttrValue {
+    // the value to set on the element, or `None` if the attribute is absent
+    fn as_value(&self) -> Option<&str> {
+        match self {
+            Self::Str(value) => Some(value),
+            Self::Bool(true) => Some(""),
+            Self::Bool(false) => None,
+        }
+    }
+}
+
+/// View state for [`DynAttrs`].
+pub struct DynAttrsState<R: DomRenderer> {
+    el: R::Element,
+    attrs: Vec<(Cow<'static, str>, DynAttrValue)>,
+    events: Vec<RemoveListener<R>>,
+}
+
+// called once to remove an event listener
+type RemoveListener<R> = Box<dyn FnOnce(&<R as Renderer>::Element)>;
+
+impl<R> DynAttrs<R>
+where
+    R: DomRenderer,
+    R::Element: Clone,
+{
+    // the entries, without any attributes that have invalid names
+    fn into_valid(self) -> impl Iterator<Item = DynAttr<R>> {
+        self.0.into_iter().filter(|attr| match attr {
+            DynAttr::Attr(key, _) => is_valid_attr_name(key),
+            DynAttr::Event(_) => true,
+        })
+    }
+
+    fn into_state<const SET: bool>(self, el: &R::Element) -> DynAttrsState<R> {
+        let mut state = DynAttrsState {
+            el: el.clone(),
+            attrs: Vec::new(),
+            events: Vec::new(),
+        };
+        for attr in self.into_valid() {
+            match attr {
+                DynAttr::Attr(key, value) => {
+                    if SET && value.as_value().is_some() {
+                        update_attr::<R>(el, &key, None, value.as_value());
+                    }
+                    state.attrs.push((key, value));
+                }
+                DynAttr::Event(event) => {
+                    state.events.push(event.attach(el));
+                }
+            }
+        }
+        state
+    }
+}
+
+impl<R> Attribute<R> for DynAttrs<R>
+where
+    R: DomRenderer,
+    R::Element: Clone,
+{
+    const MIN_LENGTH: usize = 0;
+
+    type State = DynAttrsState<R>;
+
+    fn to_html(
+        self,
+        buf: &mut String,
+        class: &mut String,
+        style: &mut String,
+        _inner_html: &mut String,
+    ) {
+        for attr in self.into_valid() {
+            let DynAttr::Attr(key, value) = attr else {
+                continue;
+            };
+            match (key.as_ref(), value) {
+                (_, DynAttrValue::Bool(false))
+                | ("class" | "style", DynAttrValue::Bool(true)) => {}
+                ("class", DynAttrValue::Str(value)) => {
+                    class.push(' ');
+                    class.push_str(&value);
+                }
+                ("style", DynAttrValue::Str(value)) => {
+                    style.push_str(&value);
+                    style.push(';');
+                }
+                (key, DynAttrValue::Str(value)) => {
+                    buf.push(' ');
+                    buf.push_str(key);
+                    buf.push_str("=\"");
+                    buf.push_str(&escape_attr(&value));
+                    buf.push('"');
+                }
+                (key, DynAttrValue::Bool(true)) => {
+                    buf.push(' ');
+                    buf.push_str(key);
+                }
+            }
+        }
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(self, el: &R::Element) -> Self::State {
+        // attributes from the server are already there, but those on an
+        // element cloned from a template need to be set
+        if FROM_SERVER {
+            self.into_state::<false>(el)
+        } else {
+            self.into_state::<true>(el)
+        }
+    }
+
+    fn build(self, el: &R::Element) -> Self::State {
+        self.into_state::<true>(el)
+    }
+
+    fn rebuild(self, state: &mut Self::State) {
+        let DynAttrsState { el, attrs, events } = state;
+
+        let mut new_attrs = Vec::new();
+        let mut new_events = Vec::new();
+        for attr in self.into_valid() {
+            match attr {
+                DynAttr::Attr(key, value) => new_attrs.push((key, value)),
+                DynAttr::Event(event) => new_events.push(event),
+            }
+        }
+
+        // remove attributes that are gone, then update the rest
+        for (key, prev) in attrs.iter() {
+            if !new_attrs.iter().any(|(new_key, _)| new_key == key) {
+                update_attr::<R>(el, key, prev.as_value(), None);
+            }
+        }
+        for (key, value) in &new_attrs {
+            let prev = attrs
+                .iter()
+                .find(|(prev_key, _)| prev_key == key)
+                .and_then(|(_, prev)| prev.as_value());
+            if prev != value.as_value() {
+                update_attr::<R>(el, key, prev, value.as_value());
+            }
+        }
+        *attrs = new_attrs;
+
+        // event listeners can't be compared, so they are all replaced
+        for remove in events.drain(..) {
+            remove(el);
+        }
+        for event in new_events {
+            events.push(event.attach(el));
+        }
+    }
+}
+
+impl<R> ToTemplate for DynAttrs<R>
+where
+    R: DomRenderer,
+{
+    fn to_template(
+        _buf: &mut String,
+        _class: &mut String,
+        _style: &mut String,
+        _inner_html: &mut String,
+        _position: &mut Position,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DynAttrs;
+    use crate::{
+        html::{
+            attribute::global::{
+                ClassAttribute, SpreadAttribute, StyleAttribute,
+            },
+            element::{div, p, ElementChild, HtmlElement},
+        },
+        renderer::{dom::Dom, mock_dom::MockDom},
+        view::{Render, RenderHtml},
+    };
+
+    #[test]
+    fn dyn_attrs_render_to_html() {
+        let attrs = DynAttrs::new()
+            .attr("id", "foo")
+            .attr("data-label", "a \"quoted\" value")
+            .attr("hidden", true)
+            .attr("disabled", false)
+            .attr("class", "extra");
+        let el: HtmlElement<_, _, _, Dom> =
+            div().spread(attrs).child(p().child("Hello"));
+        assert_eq!(
+            el.to_html(),
+            "<div id=\"foo\" data-label=\"a &quot;quoted&quot; value\" \
+             hidden class=\"extra\"><p>Hello</p></div>"
+        );
+    }
+
+    #[test]
+    fn dyn_attrs_are_diffed_on_rebuild() {
+        let el: HtmlElement<_, _, _, MockDom> = div().spread(
+            DynAttrs::new()
+                .attr("id", "foo")
+                .attr("title", "Old")
+                .attr("hidden", true),
+        );
+        let mut state = el.build();
+        assert_eq!(
+            state.el.to_debug_html(),
+            "<div id=\"foo\" title=\"Old\" hidden=\"\"></div>"
+        );
+
+        let el: HtmlElement<_, _, _, MockDom> = div().spread(
+            DynAttrs::new()
+                .attr("title", "New")
+                .attr("hidden", false)
+                .attr("lang", "en"),
+        );
+        el.rebuild(&mut state);
+        assert_eq!(
+            state.el.to_debug_html(),
+            "<div title=\"New\" lang=\"en\"></div>"
+        );
+    }
+
+    #[test]
+    fn dyn_class_and_style_merge_in_the_browser_as_on_the_server() {
+        let view = |class, style| -> HtmlElement<_, _, _, MockDom> {
+            div().class("btn").style("margin: 0").spread(
+                DynAttrs::new().attr("class", class).attr("style", style),
+            )
+        };
+        assert_eq!(
+            view("extra", "color: red").to_html(),
+            "<div class=\"btn extra\" style=\"margin: 0;color: red;\"></div>"
+        );
+
+        let mut state = view("extra", "color: red").build();
+        assert_eq!(
+            state.el.to_debug_html(),
+            "<div class=\"btn extra\" style=\"margin: 0; color: red;\"></div>"
+        );
+
+        view("other", "").rebuild(&mut state);
+        assert_eq!(
+            state.el.to_debug_html(),
+            "<div class=\"btn other\" style=\"margin: 0;\"></div>"
+        );
+    }
+
+    #[test]
+    fn dyn_attrs_with_invalid_names_are_ignored() {
+        let attrs = DynAttrs::new()
+            .attr("onclick=\"alert(1)\" x", "y")
+            .attr("a>b", "c")
+            .attr("title", "ok");
+        let el: HtmlElement<_, _, _, MockDom> = div().spread(attrs);
+        assert_eq!(el.to_html(), "<div title=\"ok\"></div>");
+    }
+}
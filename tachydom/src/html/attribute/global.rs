@@ -316,6 +316,20 @@ where {
     }
 }
 
+pub trait SpreadAttribute<A, Rndr>
+where
+    A: Attribute<Rndr>,
+    Rndr: Renderer,
+    Self: Sized + AddAttribute<A, Rndr>,
+{
+    /// Adds a whole set of attributes at once, like
+    /// [`DynAttrs`](super::dynamic::DynAttrs), which is
+    /// what `{..attrs}` does in the `view` macro.
+    fn spread(self, attrs: A) -> <Self as AddAttribute<A, Rndr>>::Output {
+        self.add_attr(attrs)
+    }
+}
+
 impl<T, Rndr, V> GlobalAttributes<Rndr, V> for T
 where
     T: AddAttribute<Attr<Accesskey, V, Rndr>, Rndr>
@@ -386,3 +400,11 @@ where
     Rndr: DomRenderer,
 {
 }
+
+impl<T, A, Rndr> SpreadAttribute<A, Rndr> for T
+where
+    T: AddAttribute<A, Rndr>,
+    A: Attribute<Rndr>,
+    Rndr: Renderer,
+{
+}
@@ -1,5 +1,6 @@
 pub mod aria;
 pub mod custom;
+pub mod dynamic;
 pub mod global;
 mod key;
 mod value;
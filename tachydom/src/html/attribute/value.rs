@@ -293,7 +293,22 @@ where
     }
 }
 
-// TODO
-fn escape_attr(value: &str) -> Cow<'_, str> {
-    value.into()
+/// Escapes a string so that it can be safely included in a double-quoted
+/// HTML attribute value.
+pub fn escape_attr(value: &str) -> Cow<'_, str> {
+    let Some(first) = value.find(['&', '"', '<', '>']) else {
+        return Cow::Borrowed(value);
+    };
+    let mut escaped = String::with_capacity(value.len() + 8);
+    escaped.push_str(&value[..first]);
+    for ch in value[first..].chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(ch),
+        }
+    }
+    Cow::Owned(escaped)
 }
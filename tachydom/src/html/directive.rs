@@ -0,0 +1,239 @@
+use super::{
+    attribute::{global::AddAttribute, Attribute},
+    element::HtmlElement,
+};
+use crate::{
+    renderer::Renderer,
+    view::{Position, Render, ToTemplate},
+};
+use std::marker::PhantomData;
+#[cfg(feature = "reaccy")]
+use tachy_reaccy::Owner;
+
+/// Creates a directive, which calls `handler` with the element and `param`
+/// once the element has been created or hydrated.
+///
+/// This is what `use:handler=param` does in the `view` macro. The handler
+/// runs under its own reactive `Owner`, so it can register anything it sets
+/// up, like event listeners or observers, to be removed with
+/// `Owner::on_cleanup` when the element is removed.
+#[inline(always)]
+pub fn directive<T, D, P, R>(handler: D, param: P) -> Directive<T, D, P, R>
+where
+    D: IntoDirective<T, P, R>,
+    R: Renderer,
+{
+    Directive {
+        handler,
+        param,
+        ty: PhantomData,
+    }
+}
+
+/// A function that is run on an element, with some value, after the element
+/// has been created.
+pub struct Directive<T, D, P, R>
+where
+    D: IntoDirective<T, P, R>,
+    R: Renderer,
+{
+    handler: D,
+    param: P,
+    ty: PhantomData<(T, R)>,
+}
+
+/// A function that can be used as a directive, taking either the element
+/// alone or the element and a value.
+pub trait IntoDirective<T, P, R: Renderer> {
+    /// Calls the function.
+    fn run(&self, el: R::Element, param: P);
+}
+
+impl<F, R> IntoDirective<(R::Element,), (), R> for F
+where
+    F: Fn(R::Element),
+    R: Renderer,
+{
+    fn run(&self, el: R::Element, _param: ()) {
+        self(el)
+    }
+}
+
+impl<F, P, R> IntoDirective<(R::Element, P), P, R> for F
+where
+    F: Fn(R::Element, P),
+    R: Renderer,
+{
+    fn run(&self, el: R::Element, param: P) {
+        self(el, param)
+    }
+}
+
+/// View state for a [`Directive`].
+pub struct DirectiveState<R: Renderer> {
+    el: R::Element,
+    // cleans up after the directive when it is rerun or the element is removed
+    #[cfg(feature = "reaccy")]
+    owner: Owner,
+}
+
+impl<T, D, P, R> Directive<T, D, P, R>
+where
+    D: IntoDirective<T, P, R>,
+    R: Renderer,
+    R::Element: Clone,
+{
+    fn run(self, el: &R::Element) -> DirectiveState<R> {
+        #[cfg(feature = "reaccy")]
+        {
+            let owner = Owner::new();
+            owner.with(|| self.handler.run(el.clone(), self.param));
+            DirectiveState {
+                el: el.clone(),
+                owner,
+            }
+        }
+        #[cfg(not(feature = "reaccy"))]
+        {
+            self.handler.run(el.clone(), self.param);
+            DirectiveState { el: el.clone() }
+        }
+    }
+}
+
+impl<T, D, P, R> Attribute<R> for Directive<T, D, P, R>
+where
+    D: IntoDirective<T, P, R>,
+    R: Renderer,
+    R::Element: Clone,
+{
+    const MIN_LENGTH: usize = 0;
+
+    type State = DirectiveState<R>;
+
+    fn to_html(
+        self,
+        _buf: &mut String,
+        _class: &mut String,
+        _style: &mut String,
+        _inner_html: &mut String,
+    ) {
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(self, el: &R::Element) -> Self::State {
+        self.run(el)
+    }
+
+    fn build(self, el: &R::Element) -> Self::State {
+        self.run(el)
+    }
+
+    fn rebuild(self, state: &mut Self::State) {
+        #[cfg(feature = "reaccy")]
+        state
+            .owner
+            .with_cleanup(|| self.handler.run(state.el.clone(), self.param));
+        #[cfg(not(feature = "reaccy"))]
+        self.handler.run(state.el.clone(), self.param);
+    }
+}
+
+impl<T, D, P, R> ToTemplate for Directive<T, D, P, R>
+where
+    D: IntoDirective<T, P, R>,
+    R: Renderer,
+{
+    fn to_template(
+        _buf: &mut String,
+        _class: &mut String,
+        _style: &mut String,
+        _inner_html: &mut String,
+        _position: &mut Position,
+    ) {
+    }
+}
+
+pub trait DirectiveAttribute<T, D, P, Rndr>
+where
+    D: IntoDirective<T, P, Rndr>,
+    Rndr: Renderer,
+{
+    fn directive(
+        self,
+        handler: D,
+        param: P,
+    ) -> <Self as AddAttribute<Directive<T, D, P, Rndr>, Rndr>>::Output
+    where
+        Self: Sized + AddAttribute<Directive<T, D, P, Rndr>, Rndr>,
+        <Self as AddAttribute<Directive<T, D, P, Rndr>, Rndr>>::Output:
+            Render<Rndr>,
+    {
+        self.add_attr(directive(handler, param))
+    }
+}
+
+impl<E, At, Ch, T, D, P, Rndr> DirectiveAttribute<T, D, P, Rndr>
+    for HtmlElement<E, At, Ch, Rndr>
+where
+    At: Attribute<Rndr>,
+    Ch: Render<Rndr>,
+    D: IntoDirective<T, P, Rndr>,
+    Rndr: Renderer,
+{
+}
+
+#[cfg(all(test, feature = "reaccy"))]
+mod tests {
+    use super::DirectiveAttribute;
+    use crate::{
+        html::element::{p, ElementChild, HtmlElement},
+        renderer::{
+            mock_dom::{Element, MockDom},
+            Renderer,
+        },
+        view::Render,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tachy_reaccy::Owner;
+
+    fn tooltip(cleanups: Arc<AtomicUsize>) -> impl Fn(Element, &'static str) {
+        move |el: Element, text: &'static str| {
+            MockDom::set_attribute(&el, "title", text);
+            let cleanups = Arc::clone(&cleanups);
+            Owner::on_cleanup(move || {
+                cleanups.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+    }
+
+    #[test]
+    fn directive_runs_on_the_built_element() {
+        let cleanups = Arc::new(AtomicUsize::new(0));
+        let el: HtmlElement<_, _, _, MockDom> = p()
+            .directive(tooltip(Arc::clone(&cleanups)), "Hi")
+            .child("x");
+        let state = el.build();
+        assert_eq!(state.el.to_debug_html(), "<p title=\"Hi\">x</p>");
+        assert_eq!(cleanups.load(Ordering::Relaxed), 0);
+
+        drop(state);
+        assert_eq!(cleanups.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn directive_is_cleaned_up_and_rerun_on_rebuild() {
+        let cleanups = Arc::new(AtomicUsize::new(0));
+        let el: HtmlElement<_, _, _, MockDom> =
+            p().directive(tooltip(Arc::clone(&cleanups)), "Old");
+        let mut state = el.build();
+
+        let el: HtmlElement<_, _, _, MockDom> =
+            p().directive(tooltip(Arc::clone(&cleanups)), "New");
+        el.rebuild(&mut state);
+        assert_eq!(state.el.to_debug_html(), "<p title=\"New\"></p>");
+        assert_eq!(cleanups.load(Ordering::Relaxed), 1);
+    }
+}
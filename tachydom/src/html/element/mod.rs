@@ -1,7 +1,7 @@
 use crate::{
-    html::attribute::Attribute,
+    html::attribute::{escape_attr, Attribute},
     hydration::Cursor,
-    renderer::{CastFrom, Renderer},
+    renderer::Renderer,
     ssr::StreamBuilder,
     view::{
         FallibleRender, Mountable, Position, PositionState, Render, RenderHtml,
@@ -12,7 +12,7 @@ use const_str_slice_concat::{
     const_concat, const_concat_with_prefix, str_from_buffer,
 };
 use next_tuple::TupleBuilder;
-use std::marker::PhantomData;
+use std::{borrow::Cow, marker::PhantomData};
 
 mod custom;
 mod elements;
@@ -125,6 +125,109 @@ where
     fn child(self, child: NewChild) -> Self::Output;
 }
 
+/// A view with a single element at its root, which attributes, classes and
+/// event listeners can be added to from outside.
+///
+/// This is how attributes written where a component is used, like
+/// `<MyComponent attr:id="foo" on:click=...>`, are forwarded onto the element
+/// that the component renders. A component opts into this by returning
+/// `impl RenderHtml<Dom> + AddAnyAttr<Dom>`.
+#[diagnostic::on_unimplemented(
+    message = "attributes can only be forwarded to a view with a single \
+               element at its root",
+    note = "to accept attributes where it is used, a component has to return \
+            `impl RenderHtml<Dom> + AddAnyAttr<Dom>`, with one element at the \
+            root of its view"
+)]
+pub trait AddAnyAttr<Rndr>
+where
+    Rndr: Renderer,
+    Rndr::Node: Clone,
+    Rndr::Element: Clone,
+{
+    type Output<SomeNewAttr: Attribute<Rndr>>: RenderHtml<Rndr>;
+
+    fn add_any_attr<NewAttr: Attribute<Rndr>>(
+        self,
+        attr: NewAttr,
+    ) -> Self::Output<NewAttr>;
+}
+
+impl<E, At, Ch, Rndr> AddAnyAttr<Rndr> for HtmlElement<E, At, Ch, Rndr>
+where
+    E: ElementType + CreateElement<Rndr>,
+    At: Attribute<Rndr>,
+    Ch: RenderHtml<Rndr>,
+    Rndr: Renderer,
+    Rndr::Node: Clone,
+    Rndr::Element: Clone,
+{
+    type Output<SomeNewAttr: Attribute<Rndr>> =
+        HtmlElement<E, (At, SomeNewAttr), Ch, Rndr>;
+
+    fn add_any_attr<NewAttr: Attribute<Rndr>>(
+        self,
+        attr: NewAttr,
+    ) -> Self::Output<NewAttr> {
+        let HtmlElement {
+            tag,
+            attributes,
+            children,
+            rndr,
+        } = self;
+        HtmlElement {
+            tag,
+            attributes: (attributes, attr),
+            children,
+            rndr,
+        }
+    }
+}
+
+/// The contents of `<script>` and `<style>` are raw text, which the browser
+/// passes to the JS or CSS parser without decoding any entities.
+fn is_raw_text(tag: &str) -> bool {
+    matches!(tag, "script" | "style")
+}
+
+/// Renders the children of a raw text element, without escaping their text.
+fn raw_text_children<Ch, Rndr>(tag: &str, children: Ch) -> String
+where
+    Ch: RenderHtml<Rndr>,
+    Rndr: Renderer,
+    Rndr::Node: Clone,
+    Rndr::Element: Clone,
+{
+    let mut text = String::with_capacity(Ch::MIN_LENGTH);
+    children.to_raw_text_with_buf(&mut text);
+    escape_raw_text_end(tag, &text).into_owned()
+}
+
+/// Breaks up anything in the text of a raw text element that would close the
+/// element early, like `</script` or `</STYLE`, by escaping its `/`. Both JS
+/// and CSS read `\/` inside a string as `/`.
+fn escape_raw_text_end<'a>(tag: &str, text: &'a str) -> Cow<'a, str> {
+    let mut escaped = String::new();
+    let mut copied = 0;
+    let mut idx = 0;
+    while let Some(found) = text[idx..].find("</") {
+        let slash = idx + found + 1;
+        let name = text.as_bytes().get(slash + 1..slash + 1 + tag.len());
+        if name.is_some_and(|name| name.eq_ignore_ascii_case(tag.as_bytes())) {
+            escaped.push_str(&text[copied..slash]);
+            escaped.push('\\');
+            copied = slash;
+        }
+        idx = slash + 1;
+    }
+    if copied == 0 {
+        Cow::Borrowed(text)
+    } else {
+        escaped.push_str(&text[copied..]);
+        Cow::Owned(escaped)
+    }
+}
+
 pub trait ElementType {
     /// The underlying native widget type that this represents.
     type Output;
@@ -257,13 +360,13 @@ where
         if !class.is_empty() {
             buf.push(' ');
             buf.push_str("class=\"");
-            buf.push_str(class.trim_start().trim_end());
+            buf.push_str(&escape_attr(class.trim_start().trim_end()));
             buf.push('"');
         }
         if !style.is_empty() {
             buf.push(' ');
             buf.push_str("style=\"");
-            buf.push_str(style.trim_start().trim_end());
+            buf.push_str(&escape_attr(style.trim_start().trim_end()));
             buf.push('"');
         }
 
@@ -272,6 +375,8 @@ where
         if !E::SELF_CLOSING {
             if !inner_html.is_empty() {
                 buf.push_str(&inner_html);
+            } else if is_raw_text(E::TAG) {
+                buf.push_str(&raw_text_children(E::TAG, self.children));
             } else {
                 // children
                 *position = Position::FirstChild;
@@ -324,13 +429,13 @@ where
         if !class.is_empty() {
             buf.push(' ');
             buf.push_str("class=\"");
-            buf.push_str(class.trim_start().trim_end());
+            buf.push_str(&escape_attr(class.trim_start().trim_end()));
             buf.push('"');
         }
         if !style.is_empty() {
             buf.push(' ');
             buf.push_str("style=\"");
-            buf.push_str(style.trim_start().trim_end());
+            buf.push_str(&escape_attr(style.trim_start().trim_end()));
             buf.push('"');
         }
 
@@ -339,9 +444,15 @@ where
 
         if !E::SELF_CLOSING {
             // children
-            *position = Position::FirstChild;
-            self.children
-                .to_html_async_with_buf::<OUT_OF_ORDER>(buffer, position);
+            if is_raw_text(E::TAG) {
+                // raw text can't contain out-of-order fragments, so these are
+                // always rendered synchronously
+                buffer.push_sync(&raw_text_children(E::TAG, self.children));
+            } else {
+                *position = Position::FirstChild;
+                self.children
+                    .to_html_async_with_buf::<OUT_OF_ORDER>(buffer, position);
+            }
 
             // closing tag
             let mut buf = String::with_capacity(3 + E::TAG.len());
@@ -358,7 +469,6 @@ where
         cursor: &Cursor<Rndr>,
         position: &PositionState,
     ) -> Self::State {
-        crate::log(&format!("hydrating {}", E::TAG));
         // non-Static custom elements need special support in templates
         // because they haven't been inserted type-wise
         if E::TAG.is_empty() && !FROM_SERVER {
@@ -371,13 +481,21 @@ where
         } else if curr_position != Position::Current {
             cursor.sibling();
         }
-        let el = Rndr::Element::cast_from(cursor.current()).unwrap();
+        let Some(el) = cursor.expect_element(E::TAG) else {
+            // the DOM didn't match, so build this subtree from scratch
+            let state = self.build();
+            cursor.replace(state.el.as_ref());
+            position.set(Position::NextChild);
+            return state;
+        };
 
         let attrs = self.attributes.hydrate::<FROM_SERVER>(&el);
 
         // hydrate children
         position.set(Position::FirstChild);
+        cursor.enter(E::TAG);
         let children = self.children.hydrate::<FROM_SERVER>(cursor, position);
+        cursor.exit();
         cursor.set(el.as_ref().clone());
 
         // go to next sibling
@@ -477,13 +595,13 @@ where
             if !class.is_empty() {
                 buf.push(' ');
                 buf.push_str("class=\"");
-                buf.push_str(class.trim_start().trim_end());
+                buf.push_str(&escape_attr(class.trim_start().trim_end()));
                 buf.push('"');
             }
             if !style.is_empty() {
                 buf.push(' ');
                 buf.push_str("style=\"");
-                buf.push_str(style.trim_start().trim_end());
+                buf.push_str(&escape_attr(style.trim_start().trim_end()));
                 buf.push('"');
             }
             buf.push('>');
@@ -512,15 +630,18 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{main, p, HtmlElement};
+    use super::{main, p, AddAnyAttr, HtmlElement};
     use crate::{
         html::{
-            attribute::{global::GlobalAttributes, id, src},
+            attribute::{
+                global::{ClassAttribute, GlobalAttributes},
+                id, src,
+            },
             class::class,
             element::{em, ElementChild, Main},
         },
         renderer::mock_dom::MockDom,
-        view::{Render, RenderHtml},
+        view::{either::Either, Render, RenderHtml},
     };
 
     #[test]
@@ -530,7 +651,7 @@ mod tests {
         let el = el.build();
         assert_eq!(
             el.el.to_debug_html(),
-            "<main><p lang=\"en\" id=\"test\">Hello, world!</p></main>"
+            "<main><p id=\"test\" lang=\"en\">Hello, world!</p></main>"
         );
     }
 
@@ -548,6 +669,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn forwarded_attributes_are_added_to_the_root_element() {
+        let el = || {
+            let el: HtmlElement<_, _, _, MockDom> =
+                p().class("a").child("Hello");
+            el.add_any_attr((id("foo"), class(("b", true))))
+        };
+        assert_eq!(el().to_html(), "<p id=\"foo\" class=\"a b\">Hello</p>");
+        let el = el().build();
+        assert_eq!(
+            el.el.to_debug_html(),
+            "<p class=\"a b\" id=\"foo\">Hello</p>"
+        );
+    }
+
+    #[test]
+    fn forwarded_attributes_are_added_to_either_branch() {
+        let el = |left| -> Either<HtmlElement<_, _, _, MockDom>, _> {
+            if left {
+                Either::Left(p().child("Left"))
+            } else {
+                Either::Right((em().child("Right"),))
+            }
+        };
+        assert_eq!(
+            el(true).add_any_attr(id("foo")).to_html(),
+            "<p id=\"foo\">Left</p><!>"
+        );
+        assert_eq!(
+            el(false).add_any_attr(id("foo")).to_html(),
+            "<em id=\"foo\">Right</em><!>"
+        );
+    }
+
     #[cfg(feature = "nightly")]
     #[test]
     fn html_render_allocates_appropriate_buffer() {
@@ -21,7 +21,12 @@ where
                 cb(specific_event);
             }) as Box<dyn FnMut(R::Event)>;
 
-            if E::BUBBLES && cfg!(feature = "delegation") {
+            // the delegated handler can't honor any listener options
+            let options = event.options();
+            if E::BUBBLES
+                && cfg!(feature = "delegation")
+                && options == ListenerOptions::default()
+            {
                 R::add_event_listener_delegated(
                     el,
                     event.name(),
@@ -29,12 +34,13 @@ where
                     cb,
                 )
             } else {
-                R::add_event_listener(el, &event.name(), cb)
+                R::add_event_listener(el, &event.name(), options, cb)
             }
         }),
         ty: PhantomData,
     }
 }
+
 pub struct On<R: DomRenderer> {
     name: Cow<'static, str>,
     #[allow(clippy::type_complexity)]
@@ -42,6 +48,21 @@ pub struct On<R: DomRenderer> {
     ty: PhantomData<R>,
 }
 
+impl<R> On<R>
+where
+    R: DomRenderer,
+{
+    /// Adds the listener to the element, and returns a function that removes
+    /// it again.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn attach(
+        self,
+        el: &R::Element,
+    ) -> Box<dyn FnOnce(&R::Element)> {
+        (self.setup)(el)
+    }
+}
+
 impl<R> Debug for On<R>
 where
     R: DomRenderer,
@@ -125,11 +146,147 @@ pub trait EventDescriptor: Clone {
     /// The key used for event delegation.
     fn event_delegation_key(&self) -> Cow<'static, str>;
 
-    /// Return the options for this type. This is only used when you create a [`Custom`] event
-    /// handler.
+    /// The options the listener is added with. Only listeners with the
+    /// default options can be delegated.
+    #[inline(always)]
+    fn options(&self) -> ListenerOptions {
+        ListenerOptions::default()
+    }
+}
+
+/// Options for an event listener, as in the DOM's `AddEventListenerOptions`.
+///
+/// Unlike the `web_sys` type, these can be created outside the browser, so a
+/// view that uses them can still be rendered on the server.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ListenerOptions {
+    /// Whether the listener is called during the capture phase, before any
+    /// listeners on descendants of the element.
+    pub capture: bool,
+    /// Whether the listener promises not to call `prevent_default()`, which
+    /// allows the browser to scroll without waiting for it.
+    pub passive: bool,
+    /// Whether the listener is removed after it is called once.
+    pub once: bool,
+}
+
+/// Adds the listener for `event` directly to the element, rather than
+/// delegating it to a single global listener.
+///
+/// This is what `on:click:undelegated` does in the `view` macro. An
+/// undelegated event can also be given [`ListenerOptions`], which is what
+/// `on:scroll:passive`, `:capture` and `:once` do.
+pub fn undelegated<E: EventDescriptor>(event: E) -> Undelegated<E> {
+    Undelegated {
+        event,
+        options: ListenerOptions::default(),
+    }
+}
+
+/// An event whose listener is added directly to the element.
+#[derive(Copy, Clone, Debug)]
+pub struct Undelegated<E> {
+    event: E,
+    options: ListenerOptions,
+}
+
+impl<E> Undelegated<E> {
+    /// Calls the listener during the capture phase.
+    pub fn capture(mut self) -> Self {
+        self.options.capture = true;
+        self
+    }
+
+    /// Marks the listener as passive.
+    pub fn passive(mut self) -> Self {
+        self.options.passive = true;
+        self
+    }
+
+    /// Removes the listener after it is called once.
+    pub fn once(mut self) -> Self {
+        self.options.once = true;
+        self
+    }
+}
+
+impl<E: EventDescriptor> EventDescriptor for Undelegated<E> {
+    type EventType = E::EventType;
+
+    const BUBBLES: bool = false;
+
+    #[inline(always)]
+    fn name(&self) -> Cow<'static, str> {
+        self.event.name()
+    }
+
+    #[inline(always)]
+    fn event_delegation_key(&self) -> Cow<'static, str> {
+        self.event.event_delegation_key()
+    }
+
+    #[inline(always)]
+    fn options(&self) -> ListenerOptions {
+        self.options
+    }
+}
+
+/// An event with any name, like one sent by a custom element, which receives
+/// `E` as its argument.
+///
+/// Listeners for custom events are always added directly to the element.
+pub struct Custom<E: FromWasmAbi = web_sys::Event> {
+    name: Cow<'static, str>,
+    ty: PhantomData<fn() -> E>,
+}
+
+impl Custom {
+    /// Creates an event with the given name, which receives a plain
+    /// [`web_sys::Event`].
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self::typed(name)
+    }
+}
+
+impl<E: FromWasmAbi> Custom<E> {
+    /// Creates an event with the given name, which receives `E`, like a
+    /// [`web_sys::CustomEvent`].
+    pub fn typed(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            ty: PhantomData,
+        }
+    }
+}
+
+impl<E: FromWasmAbi> Clone for Custom<E> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            ty: PhantomData,
+        }
+    }
+}
+
+impl<E: FromWasmAbi> Debug for Custom<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Custom").field(&self.name).finish()
+    }
+}
+
+impl<E: FromWasmAbi> EventDescriptor for Custom<E> {
+    type EventType = E;
+
+    const BUBBLES: bool = false;
+
+    #[inline(always)]
+    fn name(&self) -> Cow<'static, str> {
+        self.name.clone()
+    }
+
     #[inline(always)]
-    fn options(&self) -> &Option<web_sys::AddEventListenerOptions> {
-        &None
+    fn event_delegation_key(&self) -> Cow<'static, str> {
+        format!("$$${}", self.name).into()
     }
 }
 
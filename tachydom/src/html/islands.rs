@@ -1,38 +1,111 @@
 use crate::{
+    html::attribute::escape_attr,
     hydration::Cursor,
     prelude::{Render, RenderHtml},
     renderer::Renderer,
     ssr::StreamBuilder,
-    view::{Position, PositionState},
+    view::{Mountable, Position, PositionState},
 };
 use std::marker::PhantomData;
+#[cfg(feature = "reaccy")]
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+#[cfg(feature = "reaccy")]
+use tachy_reaccy::{Owner, WeakOwner};
 
-// TODO serialized props, too
 pub struct Island<Rndr, View> {
     component: &'static str,
+    props: Option<String>,
+    hydrate: HydrationStrategy,
     view: View,
     rndr: PhantomData<Rndr>,
 }
 const ISLAND_TAG: &'static str = "leptos-island";
 const ISLAND_CHILDREN_TAG: &'static str = "leptos-children";
 
+/// When the island script should hydrate an island.
+///
+/// This is written to the island's `data-hydrate` attribute, so that pages
+/// with many islands only hydrate the ones that are actually used.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HydrationStrategy {
+    /// Hydrate as soon as the WASM module has loaded.
+    #[default]
+    Load,
+    /// Hydrate once the browser is idle.
+    Idle,
+    /// Hydrate when the island scrolls into view.
+    Visible,
+    /// Hydrate when the user first interacts with the island, by hovering,
+    /// focusing, touching or pressing a key in it.
+    Interaction,
+    /// Hydrate once the given media query matches.
+    Media(&'static str),
+}
+
+impl HydrationStrategy {
+    /// The value of the `data-hydrate` attribute for this strategy.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HydrationStrategy::Load => "load",
+            HydrationStrategy::Idle => "idle",
+            HydrationStrategy::Visible => "visible",
+            HydrationStrategy::Interaction => "interaction",
+            HydrationStrategy::Media(_) => "media",
+        }
+    }
+}
+
 impl<Rndr, View> Island<Rndr, View> {
     pub fn new(component: &'static str, view: View) -> Self {
         Island {
             component,
+            props: None,
+            hydrate: HydrationStrategy::default(),
             view,
             rndr: PhantomData,
         }
     }
 
-    fn open_tag(component: &'static str, buf: &mut String) {
+    /// Sets the serialized props for the island, which are written to its
+    /// `data-props` attribute so that it can be hydrated with them.
+    pub fn with_props(mut self, props: impl Into<String>) -> Self {
+        self.props = Some(props.into());
+        self
+    }
+
+    /// Sets when the island should be hydrated.
+    pub fn with_hydration(mut self, hydrate: HydrationStrategy) -> Self {
+        self.hydrate = hydrate;
+        self
+    }
+
+    fn open_tag(&self, buf: &mut String) {
         buf.push('<');
         buf.push_str(ISLAND_TAG);
         buf.push(' ');
         buf.push_str("data-component=\"");
-        buf.push_str(component);
-        buf.push_str("\">");
-        // TODO insert serialized props
+        buf.push_str(self.component);
+        buf.push('"');
+        if let Some(props) = &self.props {
+            buf.push_str(" data-props=\"");
+            buf.push_str(&escape_attr(props));
+            buf.push('"');
+        }
+        // islands are hydrated on load unless they say otherwise
+        if self.hydrate != HydrationStrategy::Load {
+            buf.push_str(" data-hydrate=\"");
+            buf.push_str(self.hydrate.as_str());
+            buf.push('"');
+        }
+        if let HydrationStrategy::Media(query) = self.hydrate {
+            buf.push_str(" data-media=\"");
+            buf.push_str(&escape_attr(query));
+            buf.push('"');
+        }
+        buf.push('>');
     }
 
     fn close_tag(buf: &mut String) {
@@ -72,7 +145,7 @@ where
         + View::MIN_LENGTH;
 
     fn to_html_with_buf(self, buf: &mut String, position: &mut Position) {
-        Self::open_tag(self.component, buf);
+        self.open_tag(buf);
         self.view.to_html_with_buf(buf, position);
         Self::close_tag(buf);
     }
@@ -86,7 +159,7 @@ where
     {
         // insert the opening tag synchronously
         let mut tag = String::new();
-        Self::open_tag(self.component, &mut tag);
+        self.open_tag(&mut tag);
         buf.push_sync(&tag);
 
         // streaming render for the view
@@ -104,12 +177,152 @@ where
         cursor: &Cursor<Rndr>,
         position: &PositionState,
     ) -> Self::State {
-        crate::log("hydrating Island");
         position.set(Position::FirstChild);
-        self.view.hydrate::<FROM_SERVER>(cursor, position)
+        cursor.enter(self.component);
+        let state = self.view.hydrate::<FROM_SERVER>(cursor, position);
+        cursor.exit();
+        state
+    }
+}
+
+#[cfg(feature = "reaccy")]
+const ISLAND_ID_ATTR: &str = "data-island-id";
+
+#[cfg(feature = "reaccy")]
+thread_local! {
+    // the owner of each island that has been hydrated, keyed by the id in its
+    // `data-island-id` attribute, so that islands nested inside it can find it;
+    // entries are removed when the owner is disposed or dropped
+    static ISLAND_OWNERS: RefCell<HashMap<usize, WeakOwner>> =
+        Default::default();
+    static NEXT_ISLAND_ID: Cell<usize> = const { Cell::new(0) };
+    // set while an island is being hydrated by `with_island_owner`
+    static HYDRATING_ISLAND: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether `#[island]` components should serialize their props for the
+/// `data-props` attribute.
+///
+/// This is skipped while an island is being hydrated, because the client only
+/// ever reads props back out of the server-rendered HTML.
+#[doc(hidden)]
+#[cfg(feature = "reaccy")]
+pub fn should_serialize_props() -> bool {
+    !HYDRATING_ISLAND.with(Cell::get)
+}
+
+/// Runs `fun`, usually to hydrate the island in `el`, with a new reactive
+/// [`Owner`] for that island.
+///
+/// The new owner is a child of the owner of the nearest island around this
+/// one, so that context provided by an outer island can be used inside the
+/// islands nested in it, as it can on the server. The island script only
+/// hydrates an island once the island around it has been hydrated; if `el` is
+/// hydrated some other way before its outer island, it gets an owner with no
+/// parent.
+///
+/// The owner is returned along with the value of `fun`, and the island lives
+/// for as long as it is kept, or until the island is passed to
+/// [`dispose_island`] or the island around it is disposed.
+#[cfg(feature = "reaccy")]
+pub fn with_island_owner<Rndr, T>(
+    el: &Rndr::Element,
+    fun: impl FnOnce() -> T,
+) -> (Owner, T)
+where
+    Rndr: Renderer,
+    Rndr::Node: Clone,
+{
+    let id = NEXT_ISLAND_ID.with(|next| next.replace(next.get() + 1));
+    Rndr::set_attribute(el, ISLAND_ID_ATTR, &id.to_string());
+
+    let owner = match ancestor_island_owner::<Rndr>(el) {
+        Some(parent) => parent.with(|| {
+            // nested islands are disposed along with the island around them
+            Owner::on_cleanup(move || remove_island_owner(id));
+            Owner::new()
+        }),
+        None => Owner::new(),
+    };
+    ISLAND_OWNERS
+        .with(|owners| owners.borrow_mut().insert(id, owner.downgrade()));
+
+    let prev = HYDRATING_ISLAND.with(|hydrating| hydrating.replace(true));
+    let value = owner.with(|| {
+        Owner::on_cleanup(move || _ = forget_island_owner(id));
+        fun()
+    });
+    HYDRATING_ISLAND.with(|hydrating| hydrating.set(prev));
+    (owner, value)
+}
+
+/// Disposes of the reactive [`Owner`] of the island in `el`, and of every
+/// island nested in it, running their cleanups.
+#[cfg(feature = "reaccy")]
+pub fn dispose_island<Rndr>(el: &Rndr::Element)
+where
+    Rndr: Renderer,
+{
+    if let Some(id) = island_id::<Rndr>(el) {
+        remove_island_owner(id);
+    }
+}
+
+#[cfg(feature = "reaccy")]
+fn remove_island_owner(id: usize) {
+    // the map is released before running the island's cleanups, because they
+    // remove the owners of the islands nested in it
+    let owner = forget_island_owner(id).and_then(|owner| owner.upgrade());
+    if let Some(owner) = owner {
+        owner.with_cleanup(|| {});
+    }
+}
+
+#[cfg(feature = "reaccy")]
+fn forget_island_owner(id: usize) -> Option<WeakOwner> {
+    ISLAND_OWNERS
+        .try_with(|owners| owners.borrow_mut().remove(&id))
+        .ok()
+        .flatten()
+}
+
+#[cfg(feature = "reaccy")]
+fn island_id<Rndr>(el: &Rndr::Element) -> Option<usize>
+where
+    Rndr: Renderer,
+{
+    Rndr::get_attribute(el, ISLAND_ID_ATTR)?.parse().ok()
+}
+
+#[cfg(feature = "reaccy")]
+fn ancestor_island_owner<Rndr>(el: &Rndr::Element) -> Option<Owner>
+where
+    Rndr: Renderer,
+    Rndr::Node: Clone,
+{
+    use crate::renderer::CastFrom;
+
+    let mut node = Rndr::get_parent(el.as_ref());
+    while let Some(curr) = node {
+        if let Some(id) = Rndr::Element::cast_from(curr.clone())
+            .and_then(|el| island_id::<Rndr>(&el))
+        {
+            return ISLAND_OWNERS.with(|owners| {
+                owners.borrow().get(&id).and_then(WeakOwner::upgrade)
+            });
+        }
+        node = Rndr::get_parent(&curr);
     }
+    None
 }
 
+/// The children of an island, which are only rendered on the server.
+///
+/// The children are rendered into a `<leptos-children>` element. When the
+/// island is hydrated, that element is kept exactly as the server rendered
+/// it: its contents are not hydrated, and are never recreated on the client,
+/// so static content can be nested inside an island without shipping the code
+/// to render it.
 pub struct IslandChildren<Rndr, View> {
     view: View,
     rndr: PhantomData<Rndr>,
@@ -136,16 +349,61 @@ impl<Rndr, View> IslandChildren<Rndr, View> {
     }
 }
 
+/// The state of [`IslandChildren`], which holds on to the server-rendered
+/// `<leptos-children>` element.
+pub struct IslandChildrenState<Rndr>
+where
+    Rndr: Renderer,
+{
+    el: Option<Rndr::Element>,
+}
+
 impl<Rndr, View> Render<Rndr> for IslandChildren<Rndr, View>
 where
     View: Render<Rndr>,
     Rndr: Renderer,
 {
-    type State = ();
+    type State = IslandChildrenState<Rndr>;
+
+    // the children only exist in the server-rendered HTML, so there's
+    // nothing to create on the client
+    fn build(self) -> Self::State {
+        IslandChildrenState { el: None }
+    }
+
+    // and whatever was hydrated is left as it is
+    fn rebuild(self, _state: &mut Self::State) {}
+}
+
+impl<Rndr> Mountable<Rndr> for IslandChildrenState<Rndr>
+where
+    Rndr: Renderer,
+{
+    fn unmount(&mut self) {
+        if let Some(el) = &self.el {
+            Rndr::remove(el.as_ref());
+        }
+    }
 
-    fn build(self) -> Self::State {}
+    fn mount(&mut self, parent: &Rndr::Element, marker: Option<&Rndr::Node>) {
+        if let Some(el) = &self.el {
+            Rndr::insert_node(parent, el.as_ref(), marker);
+        }
+    }
 
-    fn rebuild(self, state: &mut Self::State) {}
+    fn insert_before_this(
+        &self,
+        parent: &Rndr::Element,
+        child: &mut dyn Mountable<Rndr>,
+    ) -> bool {
+        match &self.el {
+            Some(el) => {
+                child.mount(parent, Some(el.as_ref()));
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 impl<Rndr, View> RenderHtml<Rndr> for IslandChildren<Rndr, View>
@@ -193,7 +451,6 @@ where
         cursor: &Cursor<Rndr>,
         position: &PositionState,
     ) -> Self::State {
-        crate::log("hydrating IslandChildren");
         // island children aren't hydrated
         // we update the walk to pass over them
         // but we don't hydrate their children
@@ -203,5 +460,222 @@ where
         } else if curr_position != Position::Current {
             cursor.sibling();
         }
+        let el = cursor.expect_element(ISLAND_CHILDREN_TAG);
+        position.set(Position::NextChild);
+        IslandChildrenState { el }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HydrationStrategy, Island, IslandChildren};
+    use crate::{
+        html::element::{b, main, p, ElementChild},
+        renderer::{
+            mock_dom::{Element, MockDom},
+            CastFrom, Renderer,
+        },
+        view::{Position, RenderHtml},
+    };
+
+    #[test]
+    fn island_props_are_written_to_data_attribute() {
+        let island: Island<MockDom, _> = Island::new("Counter", "Hello")
+            .with_props(r#"{"label":"<b>Count</b>"}"#);
+        assert_eq!(
+            island.to_html(),
+            "<leptos-island data-component=\"Counter\" \
+             data-props=\"{&quot;label&quot;:&quot;&lt;b&gt;Count&lt;/\
+             b&gt;&quot;}\">Hello</leptos-island>"
+        );
+    }
+
+    #[test]
+    fn island_without_props_has_no_data_attribute() {
+        let island: Island<MockDom, _> = Island::new("Counter", "Hello");
+        assert_eq!(
+            island.to_html(),
+            "<leptos-island data-component=\"Counter\">Hello</leptos-island>"
+        );
+    }
+
+    #[test]
+    fn island_hydration_strategy_is_written_to_data_attribute() {
+        let island: Island<MockDom, _> = Island::new("Counter", "Hello")
+            .with_hydration(HydrationStrategy::Visible);
+        assert_eq!(
+            island.to_html(),
+            "<leptos-island data-component=\"Counter\" \
+             data-hydrate=\"visible\">Hello</leptos-island>"
+        );
+
+        let island: Island<MockDom, _> = Island::new("Counter", "Hello")
+            .with_hydration(HydrationStrategy::Media("(min-width: 800px)"));
+        assert_eq!(
+            island.to_html(),
+            "<leptos-island data-component=\"Counter\" \
+             data-hydrate=\"media\" data-media=\"(min-width: 800px)\">\
+             Hello</leptos-island>"
+        );
+    }
+
+    #[test]
+    fn island_children_are_left_untouched_by_hydration() {
+        let island: Island<MockDom, _> = Island::new(
+            "Wrap",
+            main().child((
+                IslandChildren::new(b().child("server only")),
+                p().child("after"),
+            )),
+        );
+        let html = island.to_html();
+        assert_eq!(
+            html,
+            "<leptos-island data-component=\"Wrap\"><main>\
+             <leptos-children><b>server only</b></leptos-children>\
+             <p>after</p></main></leptos-island>"
+        );
+
+        let body = MockDom::load_html(&html);
+        let island_el = MockDom::first_child(body.as_ref()).unwrap();
+        let main_el = MockDom::first_child(&island_el).unwrap();
+        let children_el = MockDom::first_child(&main_el).unwrap();
+        let server_only = MockDom::first_child(&children_el).unwrap();
+
+        // the client doesn't know anything about the children
+        let island: Island<MockDom, _> = Island::new(
+            "Wrap",
+            main().child((
+                IslandChildren::<MockDom, ()>::new(()),
+                p().child("after"),
+            )),
+        );
+        let island_el = Element::cast_from(island_el).unwrap();
+        let state =
+            island.hydrate_from_position::<true>(&island_el, Position::Current);
+
+        assert_eq!(body.to_debug_html(), format!("<body>{html}</body>"));
+        assert_eq!(MockDom::first_child(&children_el), Some(server_only));
+        // elements after the children are still hydrated
+        assert_eq!(state.el.as_ref(), &main_el);
+    }
+
+    #[cfg(feature = "reaccy")]
+    #[test]
+    fn nested_islands_share_context_with_outer_islands() {
+        use super::with_island_owner;
+        use tachy_reaccy::context::{provide_context, use_context};
+
+        let body = MockDom::load_html(
+            "<leptos-island data-component=\"Outer\"><leptos-children>\
+             <leptos-island data-component=\"Inner\"></leptos-island>\
+             </leptos-children></leptos-island>\
+             <leptos-island data-component=\"Sibling\"></leptos-island>",
+        );
+        let outer = MockDom::first_child(body.as_ref()).unwrap();
+        let children = MockDom::first_child(&outer).unwrap();
+        let inner = MockDom::first_child(&children).unwrap();
+        let sibling = MockDom::next_sibling(&outer).unwrap();
+        let [outer, inner, sibling] =
+            [outer, inner, sibling].map(|el| Element::cast_from(el).unwrap());
+
+        let (_outer, _) =
+            with_island_owner::<MockDom, _>(&outer, || provide_context(42));
+        let (_inner, value) =
+            with_island_owner::<MockDom, _>(&inner, use_context::<i32>);
+        assert_eq!(value, Some(42));
+        let (_sibling, value) =
+            with_island_owner::<MockDom, _>(&sibling, use_context::<i32>);
+        assert_eq!(value, None);
+    }
+
+    #[cfg(feature = "reaccy")]
+    #[test]
+    fn disposing_an_island_disposes_nested_islands() {
+        use super::{dispose_island, with_island_owner};
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+        use tachy_reaccy::Owner;
+
+        let body = MockDom::load_html(
+            "<leptos-island data-component=\"Outer\"><leptos-children>\
+             <leptos-island data-component=\"Inner\"></leptos-island>\
+             </leptos-children></leptos-island>",
+        );
+        let outer = MockDom::first_child(body.as_ref()).unwrap();
+        let children = MockDom::first_child(&outer).unwrap();
+        let inner = MockDom::first_child(&children).unwrap();
+        let [outer, inner] =
+            [outer, inner].map(|el| Element::cast_from(el).unwrap());
+
+        let cleanups = Arc::new(AtomicUsize::new(0));
+        let _owners = [&outer, &inner].map(|el| {
+            let cleanups = Arc::clone(&cleanups);
+            with_island_owner::<MockDom, _>(el, || {
+                Owner::on_cleanup(move || {
+                    cleanups.fetch_add(1, Ordering::Relaxed);
+                });
+            })
+        });
+        assert_eq!(cleanups.load(Ordering::Relaxed), 0);
+
+        dispose_island::<MockDom>(&outer);
+        assert_eq!(cleanups.load(Ordering::Relaxed), 2);
+    }
+
+    #[cfg(feature = "reaccy")]
+    #[test]
+    fn dropping_an_island_owner_forgets_it() {
+        use super::{island_id, with_island_owner, ISLAND_OWNERS};
+        use std::sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        };
+        use tachy_reaccy::Owner;
+
+        let body = MockDom::load_html(
+            "<leptos-island data-component=\"Counter\"></leptos-island>",
+        );
+        let el =
+            Element::cast_from(MockDom::first_child(body.as_ref()).unwrap())
+                .unwrap();
+
+        let cleaned_up = Arc::new(AtomicBool::new(false));
+        let (owner, _) = with_island_owner::<MockDom, _>(&el, || {
+            let cleaned_up = Arc::clone(&cleaned_up);
+            Owner::on_cleanup(move || {
+                cleaned_up.store(true, Ordering::Relaxed)
+            });
+        });
+        let id = island_id::<MockDom>(&el).unwrap();
+        assert!(ISLAND_OWNERS.with(|owners| owners.borrow().contains_key(&id)));
+
+        drop(owner);
+        assert!(cleaned_up.load(Ordering::Relaxed));
+        assert!(!ISLAND_OWNERS.with(|owners| owners.borrow().contains_key(&id)));
+    }
+
+    #[cfg(feature = "reaccy")]
+    #[test]
+    fn props_are_not_serialized_while_hydrating() {
+        use super::{
+            dispose_island, should_serialize_props, with_island_owner,
+        };
+
+        let body = MockDom::load_html(
+            "<leptos-island data-component=\"Counter\"></leptos-island>",
+        );
+        let el =
+            Element::cast_from(MockDom::first_child(body.as_ref()).unwrap())
+                .unwrap();
+
+        assert!(should_serialize_props());
+        let (_owner, hydrating) =
+            with_island_owner::<MockDom, _>(&el, should_serialize_props);
+        assert!(!hydrating);
+        assert!(should_serialize_props());
+        dispose_island::<MockDom>(&el);
     }
 }
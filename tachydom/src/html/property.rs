@@ -98,8 +98,9 @@ macro_rules! prop_type {
         where
             R: DomRenderer,
             R::Element: Clone,
+            R::PropertyValue: From<Self> + PartialEq,
         {
-            type State = (R::Element, JsValue);
+            type State = (R::Element, R::PropertyValue);
 
             fn hydrate<const FROM_SERVER: bool>(
                 self,
@@ -1,12 +1,39 @@
-use crate::renderer::Renderer;
-use std::{cell::RefCell, rc::Rc};
+use crate::renderer::{CastFrom, Renderer};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    fmt::Write,
+    rc::Rc,
+};
 
+/// Walks the existing DOM during hydration.
+///
+/// In debug builds, the cursor also keeps track of the path of components and
+/// elements it has passed through, and checks that each node it lands on is
+/// the one the view expected to find. See [`set_mismatch_behavior`] for how a
+/// mismatch is handled.
 #[derive(Debug)]
-pub struct Cursor<R: Renderer>(Rc<RefCell<R::Node>>);
+pub struct Cursor<R: Renderer> {
+    node: Rc<RefCell<R::Node>>,
+    // set when the last call to `child()` or `sibling()` found no node
+    missing: Rc<Cell<Missing>>,
+    path: Rc<RefCell<Vec<Cow<'static, str>>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Missing {
+    None,
+    Child,
+    Sibling,
+}
 
 impl<R: Renderer> Clone for Cursor<R> {
     fn clone(&self) -> Self {
-        Self(Rc::clone(&self.0))
+        Self {
+            node: Rc::clone(&self.node),
+            missing: Rc::clone(&self.missing),
+            path: Rc::clone(&self.path),
+        }
     }
 }
 
@@ -17,35 +44,348 @@ where
     R::Element: AsRef<R::Node>,
 {
     pub fn new(root: R::Element) -> Self {
-        Self(Rc::new(RefCell::new(root.as_ref().clone())))
+        Self {
+            node: Rc::new(RefCell::new(root.as_ref().clone())),
+            missing: Rc::new(Cell::new(Missing::None)),
+            path: Default::default(),
+        }
     }
 
     pub fn current(&self) -> R::Node {
-        self.0.borrow().clone()
+        self.node.borrow().clone()
     }
 
     pub fn child(&self) {
-        let mut inner = self.0.borrow_mut();
+        let mut inner = self.node.borrow_mut();
         if let Some(node) = R::first_child(&*inner) {
             *inner = node;
+            self.missing.set(Missing::None);
+        } else {
+            self.missing.set(Missing::Child);
         }
     }
 
     pub fn sibling(&self) {
-        let mut inner = self.0.borrow_mut();
+        let mut inner = self.node.borrow_mut();
         if let Some(node) = R::next_sibling(&*inner) {
             *inner = node;
+            self.missing.set(Missing::None);
+        } else {
+            self.missing.set(Missing::Sibling);
         }
     }
 
     pub fn parent(&self) {
-        let mut inner = self.0.borrow_mut();
+        let mut inner = self.node.borrow_mut();
         if let Some(node) = R::get_parent(&*inner) {
             *inner = node;
         }
+        self.missing.set(Missing::None);
     }
 
     pub fn set(&self, node: R::Node) {
-        *self.0.borrow_mut() = node;
+        *self.node.borrow_mut() = node;
+        self.missing.set(Missing::None);
+    }
+
+    /// Records that hydration has entered a component or element with the
+    /// given name. This is only used to describe where a mismatch happened,
+    /// and does nothing in release builds.
+    pub fn enter(&self, name: impl Into<Cow<'static, str>>) {
+        if cfg!(debug_assertions) {
+            self.path.borrow_mut().push(name.into());
+        }
+    }
+
+    /// Records that hydration has left the component or element most
+    /// recently passed to [`Cursor::enter`].
+    pub fn exit(&self) {
+        if cfg!(debug_assertions) {
+            self.path.borrow_mut().pop();
+        }
+    }
+
+    /// Describes the chain of components and elements being hydrated, from
+    /// the outermost inward, like `Counter → main → p`.
+    pub fn path(&self) -> String {
+        self.path.borrow().join(" → ")
+    }
+
+    /// Returns the current node as an element with the given tag.
+    ///
+    /// In debug builds, this returns `None` if the current node is not the
+    /// expected element, after reporting the mismatch. The caller should then
+    /// build the element on the client and pass it to [`Cursor::replace`].
+    /// An empty `tag` matches any element.
+    ///
+    /// ## Panics
+    /// In release builds, panics if the current node is not an element.
+    pub fn expect_element(&self, tag: &str) -> Option<R::Element> {
+        if !cfg!(debug_assertions) {
+            return Some(
+                R::Element::cast_from(self.current())
+                    .expect("couldn't cast element from node"),
+            );
+        }
+
+        let found = self.found();
+        let el = found.clone().and_then(R::Element::cast_from);
+        match el {
+            Some(el)
+                if tag.is_empty()
+                    || R::tag_name(&el).is_none_or(|found| {
+                        found.eq_ignore_ascii_case(tag)
+                    }) =>
+            {
+                Some(el)
+            }
+            _ => {
+                self.mismatch(&format!("<{tag}>"), found.as_ref());
+                None
+            }
+        }
+    }
+
+    /// Returns the current node as a text node.
+    ///
+    /// When hydrating from server-rendered HTML, debug builds also check that
+    /// the node contains the expected text. Any mismatch is reported, and the
+    /// node is then fixed or replaced with a new text node.
+    ///
+    /// ## Panics
+    /// In release builds, panics if the current node is not a text node.
+    pub fn expect_text<const FROM_SERVER: bool>(&self, text: &str) -> R::Text {
+        if !cfg!(debug_assertions) {
+            return R::Text::cast_from(self.current())
+                .expect("couldn't cast text node from node");
+        }
+
+        let found = self.found();
+        match found.clone().and_then(R::Text::cast_from) {
+            Some(node) => {
+                if FROM_SERVER {
+                    if let Some(found) = R::text_content(&node) {
+                        if found != text {
+                            self.mismatch(
+                                &describe_text(text),
+                                Some(node.as_ref()),
+                            );
+                            R::set_text(&node, text);
+                        }
+                    }
+                }
+                node
+            }
+            None => {
+                self.mismatch(&describe_text(text), found.as_ref());
+                let node = R::create_text_node(text);
+                self.replace(node.as_ref());
+                node
+            }
+        }
+    }
+
+    /// Returns the current node as a placeholder.
+    ///
+    /// In debug builds, a mismatch is reported, and a new placeholder is
+    /// inserted in place of the node that was found.
+    ///
+    /// ## Panics
+    /// In release builds, panics if the current node is not a placeholder.
+    pub fn expect_placeholder(&self) -> R::Placeholder {
+        if !cfg!(debug_assertions) {
+            return R::Placeholder::cast_from(self.current())
+                .expect("couldn't cast placeholder from node");
+        }
+
+        let found = self.found();
+        match found.clone().and_then(R::Placeholder::cast_from) {
+            Some(placeholder) => placeholder,
+            None => {
+                self.mismatch("<!>", found.as_ref());
+                let placeholder = R::create_placeholder();
+                self.replace(placeholder.as_ref());
+                placeholder
+            }
+        }
+    }
+
+    /// Puts a node that was built on the client in place of the mismatched
+    /// node at the cursor, and moves the cursor to it.
+    ///
+    /// If the cursor was not able to move to the expected node because there
+    /// was none, the new node is appended to the parent instead.
+    pub fn replace(&self, node: &R::Node) {
+        let current = self.current();
+        let (parent, anchor) = match self.missing.get() {
+            Missing::Child => (Some(current), None),
+            Missing::Sibling => (R::get_parent(&current), None),
+            Missing::None => (R::get_parent(&current), Some(current)),
+        };
+        let parent = parent
+            .and_then(R::Element::cast_from)
+            .expect("mismatched node should have a parent element");
+        R::insert_node(&parent, node, anchor.as_ref());
+        if let Some(anchor) = anchor {
+            R::remove(&anchor);
+        }
+        self.set(node.clone());
+    }
+
+    // the node the cursor landed on, if it was able to move
+    fn found(&self) -> Option<R::Node> {
+        (self.missing.get() == Missing::None).then(|| self.current())
+    }
+
+    fn mismatch(&self, expected: &str, found: Option<&R::Node>) {
+        let mut message = String::from("hydration mismatch");
+        let path = self.path();
+        if !path.is_empty() {
+            _ = write!(message, " in {path}");
+        }
+        _ = write!(
+            message,
+            ": expected {expected}, but found {}",
+            describe_node::<R>(found)
+        );
+
+        match MISMATCH_BEHAVIOR.with(Cell::get) {
+            MismatchBehavior::Panic => panic!("{message}"),
+            MismatchBehavior::Rebuild => {
+                warn(&format!("{message}; rebuilding it on the client"))
+            }
+        }
+    }
+}
+
+/// How hydration responds when the existing DOM does not match the view.
+///
+/// Mismatches are only detected in debug builds.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MismatchBehavior {
+    /// Panic with a description of the mismatch.
+    Panic,
+    /// Log a warning, and rebuild the mismatched node and its children on
+    /// the client instead of hydrating them. This is the default.
+    #[default]
+    Rebuild,
+}
+
+thread_local! {
+    static MISMATCH_BEHAVIOR: Cell<MismatchBehavior> = Default::default();
+}
+
+/// Sets how hydration on this thread responds to a mismatch between the
+/// existing DOM and the view.
+pub fn set_mismatch_behavior(behavior: MismatchBehavior) {
+    MISMATCH_BEHAVIOR.with(|b| b.set(behavior));
+}
+
+fn describe_text(text: &str) -> String {
+    format!("text {text:?}")
+}
+
+fn describe_node<R: Renderer>(node: Option<&R::Node>) -> String
+where
+    R::Node: Clone,
+{
+    let Some(node) = node else {
+        return "nothing".to_string();
+    };
+    if let Some(el) = R::Element::cast_from(node.clone()) {
+        match R::tag_name(&el) {
+            Some(tag) => format!("<{tag}>"),
+            None => "an element".to_string(),
+        }
+    } else if let Some(text) = R::Text::cast_from(node.clone()) {
+        match R::text_content(&text) {
+            Some(text) => describe_text(&text),
+            None => "a text node".to_string(),
+        }
+    } else if R::Placeholder::cast_from(node.clone()).is_some() {
+        "<!>".to_string()
+    } else {
+        "an unknown node".to_string()
+    }
+}
+
+fn warn(message: &str) {
+    #[cfg(target_arch = "wasm32")]
+    web_sys::console::warn_1(&wasm_bindgen::JsValue::from_str(message));
+    #[cfg(not(target_arch = "wasm32"))]
+    eprintln!("{message}");
+}
+
+// mismatches are only detected in debug builds
+#[cfg(all(test, debug_assertions))]
+mod tests {
+    use super::{set_mismatch_behavior, MismatchBehavior};
+    use crate::{
+        html::element::{main, p, span, ElementChild, HtmlElement},
+        renderer::{mock_dom::MockDom, Renderer},
+        view::RenderHtml,
+    };
+
+    #[test]
+    #[should_panic(
+        expected = "hydration mismatch in main → p: expected <span>, but \
+                    found <em>"
+    )]
+    fn mismatched_element_reports_path() {
+        set_mismatch_behavior(MismatchBehavior::Panic);
+        let body = MockDom::load_html("<main><p><em>Hello</em></p></main>");
+        let view: HtmlElement<_, _, _, MockDom> =
+            main().child(p().child(span().child("Hello")));
+        view.hydrate_from::<true>(&body);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "hydration mismatch in main → p: expected text \"Hello\", \
+                    but found text \"Goodbye\""
+    )]
+    fn mismatched_text_is_reported() {
+        set_mismatch_behavior(MismatchBehavior::Panic);
+        let body = MockDom::load_html("<main><p>Goodbye</p></main>");
+        let view: HtmlElement<_, _, _, MockDom> =
+            main().child(p().child("Hello"));
+        view.hydrate_from::<true>(&body);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "hydration mismatch in main: expected <p>, but found \
+                    nothing"
+    )]
+    fn missing_element_is_reported() {
+        set_mismatch_behavior(MismatchBehavior::Panic);
+        let body = MockDom::load_html("<main></main>");
+        let view: HtmlElement<_, _, _, MockDom> =
+            main().child(p().child("Hello"));
+        view.hydrate_from::<true>(&body);
+    }
+
+    #[test]
+    fn mismatched_subtree_is_rebuilt_by_default() {
+        let body = MockDom::load_html(
+            "<main><p><em>Hello</em></p><p>Goodbye</p></main>",
+        );
+        let main_el = MockDom::first_child(body.as_ref()).unwrap();
+        let first_p = MockDom::first_child(&main_el).unwrap();
+        let view: HtmlElement<_, _, _, MockDom> = main().child((
+            p().child(span().child("Hello")),
+            p().child("Hello"),
+            p().child("World"),
+        ));
+        let state = view.hydrate_from::<true>(&body);
+
+        assert_eq!(
+            body.to_debug_html(),
+            "<body><main><p><span>Hello</span></p><p>Hello</p><p>World</p>\
+             </main></body>"
+        );
+        // nodes that did match were hydrated, not replaced
+        assert_eq!(state.el.as_ref(), &main_el);
+        assert_eq!(MockDom::first_child(&main_el), Some(first_p));
     }
 }
@@ -8,12 +8,14 @@ pub mod prelude {
             attribute::{
                 aria::AriaAttributes,
                 custom::CustomAttribute,
+                dynamic::DynAttrs,
                 global::{
                     ClassAttribute, GlobalAttributes, OnAttribute,
-                    PropAttribute, StyleAttribute,
+                    PropAttribute, SpreadAttribute, StyleAttribute,
                 },
             },
-            element::{ElementChild, InnerHtmlAttribute},
+            directive::DirectiveAttribute,
+            element::{AddAnyAttr, ElementChild, InnerHtmlAttribute},
             node_ref::NodeRefAttribute,
         },
         renderer::{dom::Dom, Renderer, SpawningRenderer},
@@ -3,6 +3,7 @@ use super::SpawningRenderer;
 use super::{CastFrom, DomRenderer, Renderer};
 use crate::{
     dom::{document, window},
+    html::event::ListenerOptions,
     ok_or_debug, or_debug,
     view::Mountable,
 };
@@ -10,8 +11,8 @@ use rustc_hash::FxHashSet;
 use std::{borrow::Cow, cell::RefCell};
 use wasm_bindgen::{intern, prelude::Closure, JsCast, JsValue};
 use web_sys::{
-    Comment, CssStyleDeclaration, DocumentFragment, DomTokenList, Element,
-    HtmlElement, Node, Text,
+    AddEventListenerOptions, Comment, CssStyleDeclaration, DocumentFragment,
+    DomTokenList, Element, HtmlElement, Node, Text,
 };
 
 pub struct Dom;
@@ -89,6 +90,18 @@ impl Renderer for Dom {
         web_sys::console::log_1(node);
     }
 
+    fn tag_name(el: &Self::Element) -> Option<String> {
+        Some(el.local_name())
+    }
+
+    fn get_attribute(el: &Self::Element, name: &str) -> Option<String> {
+        el.get_attribute(name)
+    }
+
+    fn text_content(node: &Self::Text) -> Option<String> {
+        node.text_content()
+    }
+
     fn clear_children(parent: &Self::Element) {
         parent.set_text_content(Some(""));
     }
@@ -98,6 +111,7 @@ impl DomRenderer for Dom {
     type Event = JsValue;
     type ClassList = DomTokenList;
     type CssStyleDeclaration = CssStyleDeclaration;
+    type PropertyValue = JsValue;
 
     fn set_property(el: &Self::Element, key: &str, value: &JsValue) {
         or_debug!(
@@ -114,27 +128,47 @@ impl DomRenderer for Dom {
     fn add_event_listener(
         el: &Self::Element,
         name: &str,
+        options: ListenerOptions,
         cb: Box<dyn FnMut(Self::Event)>,
     ) -> Box<dyn FnOnce(&Self::Element)> {
         let cb = wasm_bindgen::closure::Closure::wrap(cb);
         let name = intern(name);
-        or_debug!(
-            el.add_event_listener_with_callback(
-                name,
-                cb.as_ref().unchecked_ref()
-            ),
-            el,
-            "addEventListener"
-        );
+        if options == ListenerOptions::default() {
+            or_debug!(
+                el.add_event_listener_with_callback(
+                    name,
+                    cb.as_ref().unchecked_ref()
+                ),
+                el,
+                "addEventListener"
+            );
+        } else {
+            let js_options = AddEventListenerOptions::new();
+            js_options.set_capture(options.capture);
+            js_options.set_passive(options.passive);
+            js_options.set_once(options.once);
+            or_debug!(
+                el.add_event_listener_with_callback_and_add_event_listener_options(
+                    name,
+                    cb.as_ref().unchecked_ref(),
+                    &js_options
+                ),
+                el,
+                "addEventListener"
+            );
+        }
 
         // return the remover
         Box::new({
             let name = name.to_owned();
             move |el| {
+                // a listener is only identified by its capture flag, not by
+                // its other options
                 or_debug!(
-                    el.remove_event_listener_with_callback(
+                    el.remove_event_listener_with_callback_and_bool(
                         intern(&name),
-                        cb.as_ref().unchecked_ref()
+                        cb.as_ref().unchecked_ref(),
+                        options.capture
                     ),
                     el,
                     "removeEventListener"
@@ -4,14 +4,23 @@
 //!
 //! Do not use this for anything real.
 
-use super::{CastFrom, DomRenderer, Renderer};
+use super::{CastFrom, DomRenderer, Renderer, SpawningRenderer};
 use crate::{
-    html::element::{CreateElement, ElementType},
+    html::{
+        element::{CreateElement, ElementType},
+        event::ListenerOptions,
+    },
     view::Mountable,
 };
-use slotmap::{new_key_type, SlotMap};
-use std::{borrow::Cow, cell::RefCell, collections::HashMap, rc::Rc};
-use wasm_bindgen::JsValue;
+use indexmap::IndexMap;
+use slotmap::{new_key_type, SecondaryMap, SlotMap};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+use wasm_bindgen::{JsCast, JsValue};
 pub struct MockDom;
 
 new_key_type! {
@@ -82,6 +91,221 @@ impl Element {
         self.debug_html(&mut buf);
         buf
     }
+
+    /// Returns the tag name of this element.
+    pub fn tag(&self) -> Cow<'static, str> {
+        Document::with_node(self.0 .0, |node| match &node.ty {
+            NodeType::Element { tag, .. } => tag.clone(),
+            _ => Cow::Borrowed(""),
+        })
+        .unwrap_or_default()
+    }
+
+    /// Returns the value of the given attribute, if it is set.
+    pub fn get_attribute(&self, name: &str) -> Option<String> {
+        Document::with_node(self.0 .0, |node| match &node.ty {
+            NodeType::Element { attrs, .. } => attrs.get(name).cloned(),
+            _ => None,
+        })
+        .flatten()
+    }
+
+    /// Returns the value of the given property, if it has been set.
+    pub fn get_property(&self, key: &str) -> Option<PropertyValue> {
+        DOCUMENT.with(|d| {
+            d.0.properties
+                .borrow()
+                .get(self.0 .0)
+                .and_then(|props| props.get(key).cloned())
+        })
+    }
+
+    /// Returns the text content of this element and all its descendants.
+    pub fn text_content(&self) -> String {
+        let mut buf = String::new();
+        self.0.text_content(&mut buf);
+        buf
+    }
+
+    /// Dispatches a synthetic event at this element.
+    ///
+    /// Like the DOM, this first calls any capturing listeners on the
+    /// element's ancestors, from the root down, and then any listeners on the
+    /// element itself and, if the event bubbles, on each of its ancestors in
+    /// turn, until one of them calls [`Event::stop_propagation`]. Delegated
+    /// handlers are then called the way the global handler in the real DOM
+    /// renderer calls them: walking up from the target, skipping disabled
+    /// elements.
+    pub fn dispatch_event(&self, event: Event) {
+        event.0.target.replace(Some(self.clone()));
+        let prev = CURRENT_EVENT.with(|e| e.replace(Some(event.clone())));
+
+        let mut ancestors = Vec::new();
+        let mut node = self.0.clone();
+        while let Some(parent) = MockDom::get_parent(&node) {
+            ancestors.push(parent.clone());
+            node = parent;
+        }
+
+        // capture phase, down to and including the target
+        let capture_path = ancestors
+            .iter()
+            .rev()
+            .chain(std::iter::once(&self.0))
+            .map(|node| (node, true));
+        // bubble phase, from the target up
+        let bubble_path = std::iter::once(&self.0)
+            .chain(ancestors.iter().filter(|_| event.bubbles()))
+            .map(|node| (node, false));
+        for (node, capture) in capture_path.chain(bubble_path) {
+            event.0.current_target.replace(Some(Element(node.clone())));
+            for (id, options, handler) in node.listeners(event.name(), capture)
+            {
+                if options.once {
+                    node.remove_listener(id);
+                }
+                event.0.passive.set(options.passive);
+                (handler.borrow_mut())(event.clone());
+                event.0.passive.set(false);
+            }
+            if event.cancel_bubble() {
+                break;
+            }
+        }
+        event.0.current_target.replace(None);
+
+        let mut path = vec![self.0.clone()];
+        if event.bubbles() {
+            path.extend(ancestors);
+        }
+
+        // delegated handlers are only reached by events that bubble all the
+        // way up to the global listener
+        if event.bubbles() && !event.cancel_bubble() {
+            let key = DOCUMENT.with(|d| {
+                d.0.delegated_events.borrow().get(event.name()).cloned()
+            });
+            if let Some(key) = key {
+                for node in &path {
+                    if node.is_disabled() {
+                        continue;
+                    }
+                    if let Some(handler) = node.delegated_handler(&key) {
+                        (handler.borrow_mut())(event.clone());
+                        if event.cancel_bubble() {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        CURRENT_EVENT.with(|e| e.replace(prev));
+    }
+}
+
+thread_local! {
+    static CURRENT_EVENT: RefCell<Option<Event>> = Default::default();
+}
+
+impl Node {
+    // clone the handlers out, so that they can freely modify the document,
+    // including adding or removing listeners
+    fn listeners(
+        &self,
+        name: &str,
+        capture: bool,
+    ) -> Vec<(usize, ListenerOptions, Handler)> {
+        DOCUMENT.with(|d| {
+            d.0.listeners
+                .borrow()
+                .get(self.0)
+                .map(|listeners| {
+                    listeners
+                        .iter()
+                        .filter(|listener| {
+                            listener.name == name
+                                && listener.options.capture == capture
+                        })
+                        .map(|listener| {
+                            (
+                                listener.id,
+                                listener.options,
+                                Rc::clone(&listener.cb),
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    fn remove_listener(&self, id: usize) {
+        DOCUMENT.with(|d| {
+            if let Some(listeners) = d.0.listeners.borrow_mut().get_mut(self.0)
+            {
+                listeners.retain(|listener| listener.id != id);
+            }
+        })
+    }
+
+    fn delegated_handler(&self, key: &str) -> Option<Handler> {
+        DOCUMENT.with(|d| {
+            d.0.delegated
+                .borrow()
+                .get(self.0)
+                .and_then(|handlers| handlers.get(key).cloned())
+        })
+    }
+
+    fn is_disabled(&self) -> bool {
+        let has_attr = Document::with_node(self.0, |node| match &node.ty {
+            NodeType::Element { attrs, .. } => attrs.contains_key("disabled"),
+            _ => false,
+        })
+        .unwrap_or(false);
+        has_attr
+            || Element(self.clone()).get_property("disabled")
+                == Some(PropertyValue::Bool(true))
+    }
+
+    fn text_content(&self, buf: &mut String) {
+        let children = Document::with_node(self.0, |node| match &node.ty {
+            NodeType::Text(text) => {
+                buf.push_str(text);
+                Vec::new()
+            }
+            NodeType::Element { children, .. } => children.clone(),
+            NodeType::Placeholder(_) => Vec::new(),
+        })
+        .unwrap_or_default();
+        for child in children {
+            child.text_content(buf);
+        }
+    }
+}
+
+impl Text {
+    /// Returns the content of this text node.
+    pub fn data(&self) -> String {
+        Document::with_node(self.0 .0, |node| match &node.ty {
+            NodeType::Text(text) => text.clone(),
+            _ => String::new(),
+        })
+        .unwrap_or_default()
+    }
+}
+
+impl Placeholder {
+    /// Returns the content of this comment node, which is empty for a `<!>`
+    /// marker.
+    pub fn data(&self) -> String {
+        Document::with_node(self.0 .0, |node| match &node.ty {
+            NodeType::Placeholder(data) => data.clone(),
+            _ => String::new(),
+        })
+        .unwrap_or_default()
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -146,13 +370,41 @@ impl DebugHtml for NodeData {
                 buf.push_str(tag);
                 buf.push('>');
             }
-            NodeType::Placeholder => buf.push_str("<!>"),
+            NodeType::Placeholder(data) if data.is_empty() => {
+                buf.push_str("<!>")
+            }
+            NodeType::Placeholder(data) => {
+                buf.push_str("<!--");
+                buf.push_str(data);
+                buf.push_str("-->");
+            }
         }
     }
 }
 
+type Handler = Rc<RefCell<Box<dyn FnMut(Event)>>>;
+
+struct Listener {
+    id: usize,
+    name: String,
+    options: ListenerOptions,
+    cb: Handler,
+}
+
+#[derive(Default)]
+struct DocumentInner {
+    nodes: RefCell<SlotMap<NodeId, NodeData>>,
+    properties: RefCell<SecondaryMap<NodeId, HashMap<String, PropertyValue>>>,
+    listeners: RefCell<SecondaryMap<NodeId, Vec<Listener>>>,
+    next_listener_id: Cell<usize>,
+    // delegated handlers, stored on each element by delegation key
+    delegated: RefCell<SecondaryMap<NodeId, HashMap<String, Handler>>>,
+    // maps each delegated event name to its delegation key
+    delegated_events: RefCell<HashMap<String, String>>,
+}
+
 #[derive(Clone)]
-pub struct Document(Rc<RefCell<SlotMap<NodeId, NodeData>>>);
+pub struct Document(Rc<DocumentInner>);
 
 impl Document {
     pub fn new() -> Self {
@@ -161,7 +413,7 @@ impl Document {
 
     fn with_node<U>(id: NodeId, f: impl FnOnce(&NodeData) -> U) -> Option<U> {
         DOCUMENT.with(|d| {
-            let data = d.0.borrow();
+            let data = d.0.nodes.borrow();
             let data = data.get(id);
             data.map(f)
         })
@@ -172,58 +424,287 @@ impl Document {
         f: impl FnOnce(&mut NodeData) -> U,
     ) -> Option<U> {
         DOCUMENT.with(|d| {
-            let mut data = d.0.borrow_mut();
+            let mut data = d.0.nodes.borrow_mut();
             let data = data.get_mut(id);
             data.map(f)
         })
     }
 
     pub fn reset(&self) {
-        self.0.borrow_mut().clear();
+        self.0.nodes.borrow_mut().clear();
+        self.0.properties.borrow_mut().clear();
+        self.0.listeners.borrow_mut().clear();
+        self.0.delegated.borrow_mut().clear();
+        self.0.delegated_events.borrow_mut().clear();
     }
 
     fn create_element(&self, tag: &str) -> Element {
-        Element(Node(self.0.borrow_mut().insert(NodeData {
+        Element(Node(self.0.nodes.borrow_mut().insert(NodeData {
             parent: None,
             ty: NodeType::Element {
                 tag: tag.to_string().into(),
-                attrs: HashMap::new(),
+                attrs: IndexMap::new(),
                 children: Vec::new(),
             },
         })))
     }
 
     fn create_text_node(&self, data: &str) -> Text {
-        Text(Node(self.0.borrow_mut().insert(NodeData {
+        Text(Node(self.0.nodes.borrow_mut().insert(NodeData {
             parent: None,
             ty: NodeType::Text(data.to_string()),
         })))
     }
 
-    fn create_placeholder(&self) -> Placeholder {
-        Placeholder(Node(self.0.borrow_mut().insert(NodeData {
+    fn create_placeholder(&self, data: &str) -> Placeholder {
+        Placeholder(Node(self.0.nodes.borrow_mut().insert(NodeData {
             parent: None,
-            ty: NodeType::Placeholder,
+            ty: NodeType::Placeholder(data.to_string()),
         })))
     }
 }
 
-// TODO!
+/// A synthetic event, which can be dispatched at an [`Element`] with
+/// [`Element::dispatch_event`].
+#[derive(Clone, Debug)]
+pub struct Event(Rc<EventInner>);
+
+#[derive(Debug)]
+struct EventInner {
+    name: String,
+    bubbles: bool,
+    target: RefCell<Option<Element>>,
+    current_target: RefCell<Option<Element>>,
+    cancel_bubble: Cell<bool>,
+    default_prevented: Cell<bool>,
+    // whether a passive listener is being called
+    passive: Cell<bool>,
+}
+
+impl Event {
+    /// Creates a new event with the given name, like `click`, that bubbles.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self::new_with_bubbles(name, true)
+    }
+
+    /// Creates a new event with the given name, like `focus`, that does not
+    /// bubble.
+    pub fn non_bubbling(name: impl Into<String>) -> Self {
+        Self::new_with_bubbles(name, false)
+    }
+
+    fn new_with_bubbles(name: impl Into<String>, bubbles: bool) -> Self {
+        Self(Rc::new(EventInner {
+            name: name.into(),
+            bubbles,
+            target: Default::default(),
+            current_target: Default::default(),
+            cancel_bubble: Default::default(),
+            default_prevented: Default::default(),
+            passive: Default::default(),
+        }))
+    }
+
+    /// The event that is currently being dispatched, if any.
+    ///
+    /// Typed event handlers don't have access to the mock event, so they can
+    /// use this to call [`Event::stop_propagation`] or similar.
+    pub fn current() -> Option<Event> {
+        CURRENT_EVENT.with(|e| e.borrow().clone())
+    }
+
+    /// The name of the event.
+    pub fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// Whether the event bubbles up through the ancestors of its target.
+    pub fn bubbles(&self) -> bool {
+        self.0.bubbles
+    }
+
+    /// The element at which the event was dispatched.
+    pub fn target(&self) -> Option<Element> {
+        self.0.target.borrow().clone()
+    }
+
+    /// The element whose listener is currently being called. This is `None`
+    /// while calling delegated handlers, as it is for the global handler in
+    /// the real DOM.
+    pub fn current_target(&self) -> Option<Element> {
+        self.0.current_target.borrow().clone()
+    }
+
+    /// Prevents the event from bubbling any further.
+    pub fn stop_propagation(&self) {
+        self.0.cancel_bubble.set(true);
+    }
+
+    /// Whether [`Event::stop_propagation`] has been called.
+    pub fn cancel_bubble(&self) -> bool {
+        self.0.cancel_bubble.get()
+    }
+
+    /// Marks the event's default action as cancelled. Like the DOM, this
+    /// does nothing when called from a passive listener.
+    pub fn prevent_default(&self) {
+        if !self.0.passive.get() {
+            self.0.default_prevented.set(true);
+        }
+    }
+
+    /// Whether [`Event::prevent_default`] has been called.
+    pub fn default_prevented(&self) -> bool {
+        self.0.default_prevented.get()
+    }
+}
+
+// Typed event handlers (like those created by `on(ev::click, ...)`) receive a
+// `web_sys` event type. There is no JavaScript runtime to back these outside
+// the browser, so the handler receives `undefined`: it will run, but should
+// not try to access the event itself.
+macro_rules! mock_event_conversions {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl From<Event> for web_sys::$ty {
+                fn from(_value: Event) -> Self {
+                    JsValue::UNDEFINED.unchecked_into()
+                }
+            }
+        )*
+    };
+}
+
+mock_event_conversions![
+    AnimationEvent,
+    BeforeUnloadEvent,
+    CompositionEvent,
+    CustomEvent,
+    DeviceMotionEvent,
+    DeviceOrientationEvent,
+    DragEvent,
+    ErrorEvent,
+    Event,
+    FocusEvent,
+    GamepadEvent,
+    HashChangeEvent,
+    InputEvent,
+    KeyboardEvent,
+    MessageEvent,
+    MouseEvent,
+    PageTransitionEvent,
+    PointerEvent,
+    PopStateEvent,
+    ProgressEvent,
+    PromiseRejectionEvent,
+    SecurityPolicyViolationEvent,
+    StorageEvent,
+    SubmitEvent,
+    TouchEvent,
+    TransitionEvent,
+    UiEvent,
+    WheelEvent,
+];
+
+/// The list of CSS classes for an element, stored in its `class` attribute.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClassList(Element);
+
+/// The CSS styles for an element, stored in its `style` attribute.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CssStyleDeclaration(Element);
+
+/// A value set as a property of an element.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropertyValue {
+    String(String),
+    Number(f64),
+    Bool(bool),
+}
+
+impl From<String> for PropertyValue {
+    fn from(value: String) -> Self {
+        PropertyValue::String(value)
+    }
+}
+
+impl From<&String> for PropertyValue {
+    fn from(value: &String) -> Self {
+        PropertyValue::String(value.clone())
+    }
+}
+
+impl From<&str> for PropertyValue {
+    fn from(value: &str) -> Self {
+        PropertyValue::String(value.to_string())
+    }
+}
+
+impl From<bool> for PropertyValue {
+    fn from(value: bool) -> Self {
+        PropertyValue::Bool(value)
+    }
+}
+
+macro_rules! number_property {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl From<$ty> for PropertyValue {
+                fn from(value: $ty) -> Self {
+                    PropertyValue::Number(value as f64)
+                }
+            }
+        )*
+    };
+}
+
+number_property![
+    usize, u8, u16, u32, u64, u128, isize, i8, i16, i32, i64, i128, f32,
+];
+
+impl From<f64> for PropertyValue {
+    fn from(value: f64) -> Self {
+        PropertyValue::Number(value)
+    }
+}
+
 impl DomRenderer for MockDom {
-    type Event = ();
-    type ClassList = ();
-    type CssStyleDeclaration = ();
+    type Event = Event;
+    type ClassList = ClassList;
+    type CssStyleDeclaration = CssStyleDeclaration;
+    type PropertyValue = PropertyValue;
 
-    fn set_property(el: &Self::Element, key: &str, value: &JsValue) {
-        todo!()
+    fn set_property(el: &Self::Element, key: &str, value: &PropertyValue) {
+        DOCUMENT.with(|d| {
+            let mut properties = d.0.properties.borrow_mut();
+            if let Some(props) = properties.entry(el.0 .0) {
+                props.or_default().insert(key.to_string(), value.clone());
+            }
+        });
     }
 
     fn add_event_listener(
         el: &Self::Element,
         name: &str,
+        options: ListenerOptions,
         cb: Box<dyn FnMut(Self::Event)>,
     ) -> Box<dyn FnOnce(&Self::Element)> {
-        todo!()
+        let id = DOCUMENT.with(|d| {
+            let id = d.0.next_listener_id.get();
+            d.0.next_listener_id.set(id + 1);
+            if let Some(listeners) = d.0.listeners.borrow_mut().entry(el.0 .0) {
+                listeners.or_default().push(Listener {
+                    id,
+                    name: name.to_string(),
+                    options,
+                    cb: Rc::new(RefCell::new(cb)),
+                });
+            }
+            id
+        });
+
+        // return the remover
+        Box::new(move |el| el.0.remove_listener(id))
     }
 
     fn add_event_listener_delegated(
@@ -232,23 +713,61 @@ impl DomRenderer for MockDom {
         delegation_key: Cow<'static, str>,
         cb: Box<dyn FnMut(Self::Event)>,
     ) -> Box<dyn FnOnce(&Self::Element)> {
-        todo!()
+        DOCUMENT.with(|d| {
+            d.0.delegated_events
+                .borrow_mut()
+                .entry(name.to_string())
+                .or_insert_with(|| delegation_key.to_string());
+            if let Some(handlers) = d.0.delegated.borrow_mut().entry(el.0 .0) {
+                handlers.or_default().insert(
+                    delegation_key.to_string(),
+                    Rc::new(RefCell::new(cb)),
+                );
+            }
+        });
+
+        // return the remover
+        Box::new(move |el| {
+            DOCUMENT.with(|d| {
+                if let Some(handlers) =
+                    d.0.delegated.borrow_mut().get_mut(el.0 .0)
+                {
+                    handlers.remove(delegation_key.as_ref());
+                }
+            })
+        })
     }
 
     fn class_list(el: &Self::Element) -> Self::ClassList {
-        todo!()
+        ClassList(el.clone())
     }
 
     fn add_class(class_list: &Self::ClassList, name: &str) {
-        todo!()
+        let el = &class_list.0;
+        let mut classes = el.get_attribute("class").unwrap_or_default();
+        if !classes.split_whitespace().any(|class| class == name) {
+            if !classes.is_empty() {
+                classes.push(' ');
+            }
+            classes.push_str(name);
+            MockDom::set_attribute(el, "class", &classes);
+        }
     }
 
     fn remove_class(class_list: &Self::ClassList, name: &str) {
-        todo!()
+        let el = &class_list.0;
+        if let Some(classes) = el.get_attribute("class") {
+            let classes = classes
+                .split_whitespace()
+                .filter(|class| *class != name)
+                .collect::<Vec<_>>()
+                .join(" ");
+            MockDom::set_attribute(el, "class", &classes);
+        }
     }
 
     fn style(el: &Self::Element) -> Self::CssStyleDeclaration {
-        todo!()
+        CssStyleDeclaration(el.clone())
     }
 
     fn set_css_property(
@@ -256,11 +775,33 @@ impl DomRenderer for MockDom {
         name: &str,
         value: &str,
     ) {
-        todo!()
+        let el = &style.0;
+        let prev = el.get_attribute("style").unwrap_or_default();
+        let mut properties = prev
+            .split(';')
+            .filter_map(|decl| {
+                let (name, value) = decl.split_once(':')?;
+                Some((name.trim().to_string(), value.trim().to_string()))
+            })
+            .collect::<IndexMap<_, _>>();
+        if value.is_empty() {
+            properties.shift_remove(name);
+        } else {
+            properties.insert(name.to_string(), value.to_string());
+        }
+        let style = properties
+            .iter()
+            .map(|(name, value)| format!("{name}: {value};"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        MockDom::set_attribute(el, "style", &style);
     }
 
     fn set_inner_html(el: &Self::Element, html: &str) {
-        todo!()
+        MockDom::clear_children(el);
+        for node in parse_html(html) {
+            MockDom::insert_node(el, &node, None);
+        }
     }
 }
 
@@ -283,15 +824,15 @@ pub enum NodeType {
     Text(String),
     Element {
         tag: Cow<'static, str>,
-        attrs: HashMap<String, String>,
+        attrs: IndexMap<String, String>,
         children: Vec<Node>,
     },
-    Placeholder,
+    Placeholder(String),
 }
 
 impl Mountable<MockDom> for Node {
     fn unmount(&mut self) {
-        todo!()
+        MockDom::remove(self);
     }
 
     fn mount(&mut self, parent: &Element, marker: Option<&Node>) {
@@ -310,7 +851,7 @@ impl Mountable<MockDom> for Node {
 
 impl Mountable<MockDom> for Text {
     fn unmount(&mut self) {
-        todo!()
+        MockDom::remove(self.as_ref());
     }
 
     fn mount(&mut self, parent: &Element, marker: Option<&Node>) {
@@ -329,7 +870,7 @@ impl Mountable<MockDom> for Text {
 
 impl Mountable<MockDom> for Element {
     fn unmount(&mut self) {
-        todo!()
+        MockDom::remove(self.as_ref());
     }
 
     fn mount(&mut self, parent: &Element, marker: Option<&Node>) {
@@ -348,7 +889,7 @@ impl Mountable<MockDom> for Element {
 
 impl Mountable<MockDom> for Placeholder {
     fn unmount(&mut self) {
-        todo!()
+        MockDom::remove(self.as_ref());
     }
 
     fn mount(&mut self, parent: &Element, marker: Option<&Node>) {
@@ -371,6 +912,21 @@ impl<E: ElementType> CreateElement<MockDom> for E {
     }
 }
 
+fn is_child_of(parent: &Element, node: &Node) -> bool {
+    MockDom::get_parent(node).as_ref() == Some(&parent.0)
+}
+
+fn is_inclusive_ancestor(ancestor: &Node, node: &Node) -> bool {
+    let mut current = Some(node.clone());
+    while let Some(node) = current {
+        if &node == ancestor {
+            return true;
+        }
+        current = MockDom::get_parent(&node);
+    }
+    false
+}
+
 impl Renderer for MockDom {
     type Node = Node;
     type Text = Text;
@@ -382,7 +938,7 @@ impl Renderer for MockDom {
     }
 
     fn create_placeholder() -> Self::Placeholder {
-        document().create_placeholder()
+        document().create_placeholder("")
     }
 
     fn set_text(node: &Self::Text, text: &str) {
@@ -404,7 +960,7 @@ impl Renderer for MockDom {
     fn remove_attribute(node: &Self::Element, name: &str) {
         Document::with_node_mut(node.0 .0, |node| {
             if let NodeType::Element { ref mut attrs, .. } = node.ty {
-                attrs.remove(name);
+                attrs.shift_remove(name);
             }
         });
     }
@@ -414,7 +970,21 @@ impl Renderer for MockDom {
         new_child: &Self::Node,
         anchor: Option<&Self::Node>,
     ) {
-        debug_assert!(&parent.0 != new_child);
+        // like the DOM, refuse to create a cycle
+        assert!(
+            !is_inclusive_ancestor(new_child, &parent.0),
+            "tried to insert a node into itself or one of its descendants"
+        );
+        if let Some(anchor) = anchor {
+            assert!(
+                is_child_of(parent, anchor),
+                "anchor is not a child of the parent"
+            );
+            // inserting a node before itself leaves it where it is
+            if anchor == new_child {
+                return;
+            }
+        }
         // remove if already mounted
         if let Some(parent) = MockDom::get_parent(new_child) {
             let parent = Element(parent);
@@ -455,10 +1025,8 @@ impl Renderer for MockDom {
                 ref mut children, ..
             } = parent.ty
             {
-                let current_pos = children
-                    .iter()
-                    .position(|item| item.0 == child.0)
-                    .expect("anchor is not a child of the parent");
+                let current_pos =
+                    children.iter().position(|item| item.0 == child.0)?;
                 Some(children.remove(current_pos))
             } else {
                 None
@@ -472,12 +1040,10 @@ impl Renderer for MockDom {
     }
 
     fn remove(node: &Self::Node) {
-        let parent = Element(Node(
-            Self::get_parent(node)
-                .expect("tried to remove a parentless node")
-                .0,
-        ));
-        Self::remove_node(&parent, node);
+        // like `ChildNode.remove()`, this does nothing if there's no parent
+        if let Some(parent) = Self::get_parent(node) {
+            Self::remove_node(&Element(parent), node);
+        }
     }
 
     fn get_parent(node: &Self::Node) -> Option<Self::Node> {
@@ -489,8 +1055,8 @@ impl Renderer for MockDom {
     fn first_child(node: &Self::Node) -> Option<Self::Node> {
         Document::with_node(node.0, |node| match &node.ty {
             NodeType::Text(_) => None,
-            NodeType::Element { children, .. } => children.get(0).cloned(),
-            NodeType::Placeholder => None,
+            NodeType::Element { children, .. } => children.first().cloned(),
+            NodeType::Placeholder(_) => None,
         })
         .flatten()
     }
@@ -521,6 +1087,18 @@ impl Renderer for MockDom {
         println!("{node:?}");
     }
 
+    fn tag_name(el: &Self::Element) -> Option<String> {
+        Some(el.tag().into_owned())
+    }
+
+    fn get_attribute(el: &Self::Element, name: &str) -> Option<String> {
+        el.get_attribute(name)
+    }
+
+    fn text_content(node: &Self::Text) -> Option<String> {
+        Some(node.data())
+    }
+
     fn clear_children(parent: &Self::Element) {
         let prev_children =
             Document::with_node_mut(parent.0 .0, |node| match node.ty {
@@ -538,6 +1116,16 @@ impl Renderer for MockDom {
     }
 }
 
+#[cfg(feature = "tokio")]
+impl SpawningRenderer for MockDom {
+    type Spawn = crate::spawner::tokio::Tokio;
+}
+
+#[cfg(not(feature = "tokio"))]
+impl SpawningRenderer for MockDom {
+    type Spawn = crate::spawner::BlockSpawn;
+}
+
 impl CastFrom<Node> for Text {
     fn cast_from(source: Node) -> Option<Self> {
         Document::with_node(source.0, |node| {
@@ -559,18 +1147,345 @@ impl CastFrom<Node> for Element {
 impl CastFrom<Node> for Placeholder {
     fn cast_from(source: Node) -> Option<Self> {
         Document::with_node(source.0, |node| {
-            matches!(node.ty, NodeType::Placeholder)
+            matches!(node.ty, NodeType::Placeholder(_))
         })
         .and_then(|matches| matches.then_some(Placeholder(Node(source.0))))
     }
 }
 
+impl MockDom {
+    /// Loads server-rendered HTML into a new, detached `<body>` element, which
+    /// can then be hydrated with
+    /// [`RenderHtml::hydrate_from`](crate::view::RenderHtml::hydrate_from).
+    ///
+    /// `<!>` markers and other comments are loaded as placeholders. If the
+    /// HTML came from an out-of-order stream, each resolved fragment is
+    /// swapped in the way its inline script would do it in the browser: the
+    /// `<!--s-..-o-->`/`<!--s-..-c-->` markers and the fallback between them
+    /// are replaced by the contents of the matching `<template>`, and the
+    /// template and the scripts around it are removed. Fragments that never
+    /// resolved are left as they are.
+    pub fn load_html(html: &str) -> Element {
+        let body = document().create_element("body");
+        for node in parse_html(html) {
+            MockDom::insert_node(&body, &node, None);
+        }
+        swap_out_of_order_fragments(&body);
+        body
+    }
+}
+
+fn swap_out_of_order_fragments(root: &Element) {
+    // nested fragments are streamed after their parents, so swapping in
+    // document order means each fragment's markers exist by the time we
+    // reach its template
+    let templates = descendants(root.as_ref())
+        .into_iter()
+        .filter_map(Element::cast_from)
+        .filter(|el| el.tag() == "template");
+    for template in templates {
+        let Some(id) = template
+            .get_attribute("id")
+            .and_then(|id| id.strip_suffix('f').map(str::to_owned))
+        else {
+            continue;
+        };
+        let (Some(open), Some(close)) = (
+            find_comment(root, &format!("s-{id}o")),
+            find_comment(root, &format!("s-{id}c")),
+        ) else {
+            continue;
+        };
+        let Some(parent) =
+            MockDom::get_parent(close.as_ref()).and_then(Element::cast_from)
+        else {
+            continue;
+        };
+
+        // remove the opening marker and the fallback
+        let mut next = Some(open.0);
+        while let Some(node) = next.filter(|node| node != close.as_ref()) {
+            next = MockDom::next_sibling(&node);
+            MockDom::remove(&node);
+        }
+        while let Some(child) = MockDom::first_child(template.as_ref()) {
+            MockDom::insert_node(&parent, &child, Some(close.as_ref()));
+        }
+        MockDom::remove(close.as_ref());
+
+        // the script that swaps it in, and the one that defines the swap
+        // function before the first fragment, remove themselves
+        let scripts = [
+            MockDom::next_sibling(template.as_ref()),
+            previous_sibling(template.as_ref()),
+        ];
+        for script in scripts
+            .into_iter()
+            .flatten()
+            .filter_map(Element::cast_from)
+            .filter(|el| el.tag() == "script")
+        {
+            MockDom::remove(script.as_ref());
+        }
+        MockDom::remove(template.as_ref());
+    }
+}
+
+fn descendants(node: &Node) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    let mut next = MockDom::first_child(node);
+    while let Some(child) = next {
+        next = MockDom::next_sibling(&child);
+        nodes.push(child.clone());
+        nodes.extend(descendants(&child));
+    }
+    nodes
+}
+
+fn previous_sibling(node: &Node) -> Option<Node> {
+    let parent = MockDom::get_parent(node)?;
+    let mut prev = None;
+    let mut next = MockDom::first_child(&parent);
+    while let Some(child) = next {
+        if &child == node {
+            return prev;
+        }
+        next = MockDom::next_sibling(&child);
+        prev = Some(child);
+    }
+    None
+}
+
+fn find_comment(root: &Element, data: &str) -> Option<Placeholder> {
+    descendants(root.as_ref())
+        .into_iter()
+        .filter_map(Placeholder::cast_from)
+        .find(|comment| comment.data() == data)
+}
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta",
+    "param", "source", "track", "wbr",
+];
+
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Parses an HTML fragment into a list of new, unmounted nodes.
+///
+/// This is not a spec-compliant HTML parser: it handles well-formed markup,
+/// void elements, comments (which become placeholders), and character
+/// references, which is all that's needed for the HTML generated by this
+/// crate.
+fn parse_html(html: &str) -> Vec<Node> {
+    let doc = document();
+    let mut roots = Vec::new();
+    let mut open: Vec<Element> = Vec::new();
+    let mut rest = html;
+
+    let mut append = |open: &[Element], node: Node| match open.last() {
+        Some(parent) => MockDom::insert_node(parent, &node, None),
+        None => roots.push(node),
+    };
+
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment.find("-->").unwrap_or(comment.len());
+            let data = &comment[..end];
+            rest = comment.get(end + 3..).unwrap_or_default();
+            append(&open, doc.create_placeholder(data).0);
+        } else if let Some(bogus) = rest.strip_prefix("<!") {
+            // either a `<!>` marker or a `<!DOCTYPE>`
+            let end = bogus.find('>').unwrap_or(bogus.len());
+            let content = &bogus[..end];
+            rest = bogus.get(end + 1..).unwrap_or_default();
+            if !content.to_ascii_lowercase().starts_with("doctype") {
+                append(&open, doc.create_placeholder("").0);
+            }
+        } else if let Some(closing) = rest.strip_prefix("</") {
+            let end = closing.find('>').unwrap_or(closing.len());
+            let tag = closing[..end].trim().to_ascii_lowercase();
+            rest = closing.get(end + 1..).unwrap_or_default();
+            // close everything up to the matching element, if there is one
+            if let Some(pos) = open.iter().rposition(|el| el.tag() == tag) {
+                open.truncate(pos);
+            }
+        } else if rest.starts_with('<')
+            && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            let (tag, attrs, self_closing, remaining) = parse_open_tag(rest);
+            rest = remaining;
+            let el = doc.create_element(&tag);
+            for (name, value) in attrs {
+                MockDom::set_attribute(&el, &name, &value);
+            }
+            append(&open, el.0.clone());
+
+            if RAW_TEXT_ELEMENTS.contains(&tag.as_str()) {
+                let end = find_closing_tag(rest, &tag).unwrap_or(rest.len());
+                let content = &rest[..end];
+                if !content.is_empty() {
+                    let content = if tag == "script" || tag == "style" {
+                        Cow::Borrowed(content)
+                    } else {
+                        decode_entities(content)
+                    };
+                    let text = doc.create_text_node(&content);
+                    MockDom::insert_node(&el, &text.0, None);
+                }
+                rest = &rest[end..];
+                // the closing tag itself is handled on the next iteration
+                open.push(el);
+            } else if !self_closing && !VOID_ELEMENTS.contains(&tag.as_str()) {
+                open.push(el);
+            }
+        } else {
+            // text runs until the next tag, comment, or marker
+            let end = rest
+                .char_indices()
+                .skip(1)
+                .find(|(idx, ch)| *ch == '<' && starts_markup(&rest[idx + 1..]))
+                .map(|(idx, _)| idx)
+                .unwrap_or(rest.len());
+            let text = doc.create_text_node(&decode_entities(&rest[..end]));
+            append(&open, text.0);
+            rest = &rest[end..];
+        }
+    }
+
+    roots
+}
+
+fn starts_markup(input: &str) -> bool {
+    input.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!')
+}
+
+fn parse_open_tag(input: &str) -> (String, Vec<(String, String)>, bool, &str) {
+    let input = &input[1..];
+    let name_end = input
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .unwrap_or(input.len());
+    let tag = input[..name_end].to_ascii_lowercase();
+    let mut rest = &input[name_end..];
+    let mut attrs = Vec::new();
+    let mut self_closing = false;
+
+    loop {
+        rest = rest.trim_start();
+        if let Some(remaining) = rest.strip_prefix("/>") {
+            self_closing = true;
+            rest = remaining;
+            break;
+        } else if let Some(remaining) = rest.strip_prefix('>') {
+            rest = remaining;
+            break;
+        } else if let Some(remaining) = rest.strip_prefix('/') {
+            rest = remaining;
+            continue;
+        } else if rest.is_empty() {
+            break;
+        }
+
+        let name_end = rest
+            .find(|c: char| {
+                c.is_whitespace() || c == '=' || c == '>' || c == '/'
+            })
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let value = if let Some(value) = rest.strip_prefix('=') {
+            let value = value.trim_start();
+            match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let value = &value[1..];
+                    let end = value.find(quote).unwrap_or(value.len());
+                    rest = value.get(end + 1..).unwrap_or_default();
+                    decode_entities(&value[..end]).into_owned()
+                }
+                _ => {
+                    let end = value
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(value.len());
+                    rest = &value[end..];
+                    decode_entities(&value[..end]).into_owned()
+                }
+            }
+        } else {
+            String::new()
+        };
+        attrs.push((name, value));
+    }
+
+    (tag, attrs, self_closing, rest)
+}
+
+fn find_closing_tag(input: &str, tag: &str) -> Option<usize> {
+    let lower = input.to_ascii_lowercase();
+    let closing = format!("</{tag}");
+    lower.find(&closing)
+}
+
+fn decode_entities(value: &str) -> Cow<'_, str> {
+    if !value.contains('&') {
+        return Cow::Borrowed(value);
+    }
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..].find(';').map(|end| &rest[1..end + 1]);
+        let ch = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => {
+                let code = entity.strip_prefix('#')?;
+                let code = match code.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => code.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+        match (ch, entity) {
+            (Some(ch), Some(entity)) => {
+                decoded.push(ch);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    Cow::Owned(decoded)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::MockDom;
+    use super::{Element, Event, MockDom, Placeholder, PropertyValue, Text};
     use crate::{
-        html::element,
-        renderer::{mock_dom::node_eq, Renderer},
+        html::{
+            attribute::global::{
+                ClassAttribute, OnAttribute, PropAttribute, StyleAttribute,
+            },
+            element::{
+                self, button, div, input, main, p, span, ElementChild,
+                HtmlElement,
+            },
+            event::{self, ListenerOptions},
+        },
+        renderer::{mock_dom::node_eq, CastFrom, DomRenderer, Renderer},
+        view::{Mountable, Render, RenderHtml},
+    };
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
     };
 
     #[test]
@@ -688,4 +1603,551 @@ mod tests {
             Some(text.as_ref())
         );
     }
+
+    #[test]
+    fn unmount_removes_node() {
+        let main = MockDom::create_element(element::Main);
+        let mut p = MockDom::create_element(element::P);
+        MockDom::insert_node(&main, p.as_ref(), None);
+        p.unmount();
+        assert_eq!(main.to_debug_html(), "<main></main>");
+        // unmounting a node without a parent does nothing
+        p.unmount();
+    }
+
+    #[test]
+    fn insert_before_self_is_noop() {
+        let main = MockDom::create_element(element::Main);
+        let p = MockDom::create_element(element::P);
+        let span = MockDom::create_element(element::Span);
+        MockDom::insert_node(&main, p.as_ref(), None);
+        MockDom::insert_node(&main, span.as_ref(), None);
+        MockDom::insert_node(&main, p.as_ref(), Some(p.as_ref()));
+        assert_eq!(main.to_debug_html(), "<main><p></p><span></span></main>");
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_into_descendant_panics() {
+        let main = MockDom::create_element(element::Main);
+        let p = MockDom::create_element(element::P);
+        MockDom::insert_node(&main, p.as_ref(), None);
+        MockDom::insert_node(&p, main.as_ref(), None);
+    }
+
+    #[test]
+    fn classes_and_styles_update_attributes() {
+        let el: HtmlElement<_, _, _, MockDom> = div()
+            .class(("foo", true))
+            .class(("bar", false))
+            .style(("color", "red"));
+        let el = el.build();
+        assert_eq!(
+            el.el.to_debug_html(),
+            "<div class=\"foo\" style=\"color: red;\"></div>"
+        );
+
+        let class_list = MockDom::class_list(&el.el);
+        MockDom::add_class(&class_list, "bar");
+        MockDom::remove_class(&class_list, "foo");
+        let style = MockDom::style(&el.el);
+        MockDom::set_css_property(&style, "height", "40px");
+        MockDom::set_css_property(&style, "color", "");
+        assert_eq!(
+            el.el.to_debug_html(),
+            "<div class=\"bar\" style=\"height: 40px;\"></div>"
+        );
+    }
+
+    #[test]
+    fn properties_are_stored() {
+        let el: HtmlElement<_, _, _, MockDom> =
+            input().prop("value", "hello").prop("checked", true);
+        let el = el.build();
+        assert_eq!(
+            el.el.get_property("value"),
+            Some(PropertyValue::String("hello".into()))
+        );
+        assert_eq!(
+            el.el.get_property("checked"),
+            Some(PropertyValue::Bool(true))
+        );
+        assert_eq!(el.el.get_property("disabled"), None);
+    }
+
+    #[test]
+    fn inner_html_is_parsed() {
+        let main = MockDom::create_element(element::Main);
+        MockDom::set_inner_html(
+            &main,
+            "<p id='a' hidden>Tom &amp; Jerry<br/><input value=x></p><!>\
+             <!-- comment -->",
+        );
+        assert_eq!(
+            main.to_debug_html(),
+            "<main><p id=\"a\" hidden=\"\">Tom & \
+             Jerry<br></br><input value=\"x\"></input></p><!><!-- comment --></main>"
+        );
+        MockDom::set_inner_html(&main, "plain text");
+        assert_eq!(main.to_debug_html(), "<main>plain text</main>");
+    }
+
+    #[test]
+    fn event_listeners_can_be_added_and_removed() {
+        let count = Rc::new(Cell::new(0));
+        let el = MockDom::create_element(element::Button);
+        let remove = MockDom::add_event_listener(
+            &el,
+            "click",
+            ListenerOptions::default(),
+            Box::new({
+                let count = Rc::clone(&count);
+                move |ev: Event| {
+                    assert_eq!(ev.name(), "click");
+                    count.set(count.get() + 1);
+                }
+            }),
+        );
+        el.dispatch_event(Event::new("click"));
+        el.dispatch_event(Event::new("input"));
+        assert_eq!(count.get(), 1);
+        remove(&el);
+        el.dispatch_event(Event::new("click"));
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn typed_event_handlers_run() {
+        let clicked = Rc::new(Cell::new(false));
+        let el: HtmlElement<_, _, _, MockDom> = button().on(event::click, {
+            let clicked = Rc::clone(&clicked);
+            move |_| clicked.set(true)
+        });
+        let el = el.build();
+        el.el.dispatch_event(Event::new("click"));
+        assert!(clicked.get());
+    }
+
+    fn log_to(
+        log: &Rc<RefCell<Vec<String>>>,
+        entry: &str,
+    ) -> Box<dyn FnMut(Event)> {
+        let log = Rc::clone(log);
+        let entry = entry.to_string();
+        Box::new(move |_| log.borrow_mut().push(entry.clone()))
+    }
+
+    #[test]
+    fn events_bubble_to_ancestors() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let main = MockDom::create_element(element::Main);
+        let p = MockDom::create_element(element::P);
+        MockDom::insert_node(&main, p.as_ref(), None);
+        MockDom::add_event_listener(
+            &main,
+            "click",
+            ListenerOptions::default(),
+            log_to(&log, "main"),
+        );
+        MockDom::add_event_listener(
+            &p,
+            "click",
+            ListenerOptions::default(),
+            log_to(&log, "p"),
+        );
+
+        p.dispatch_event(Event::new("click"));
+        assert_eq!(*log.borrow(), ["p", "main"]);
+
+        log.borrow_mut().clear();
+        p.dispatch_event(Event::non_bubbling("click"));
+        assert_eq!(*log.borrow(), ["p"]);
+    }
+
+    #[test]
+    fn stop_propagation_stops_bubbling() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let main = MockDom::create_element(element::Main);
+        let p = MockDom::create_element(element::P);
+        MockDom::insert_node(&main, p.as_ref(), None);
+        MockDom::add_event_listener(
+            &main,
+            "click",
+            ListenerOptions::default(),
+            log_to(&log, "main"),
+        );
+        MockDom::add_event_listener(
+            &p,
+            "click",
+            ListenerOptions::default(),
+            Box::new({
+                let log = Rc::clone(&log);
+                move |ev: Event| {
+                    assert_eq!(ev.current_target(), ev.target());
+                    log.borrow_mut().push("p".to_string());
+                    ev.stop_propagation();
+                }
+            }),
+        );
+        p.dispatch_event(Event::new("click"));
+        assert_eq!(*log.borrow(), ["p"]);
+    }
+
+    #[test]
+    fn capturing_listeners_run_before_the_target() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let main = MockDom::create_element(element::Main);
+        let p = MockDom::create_element(element::P);
+        MockDom::insert_node(&main, p.as_ref(), None);
+        let capture = ListenerOptions {
+            capture: true,
+            ..Default::default()
+        };
+        MockDom::add_event_listener(
+            &main,
+            "focus",
+            ListenerOptions::default(),
+            log_to(&log, "main"),
+        );
+        MockDom::add_event_listener(
+            &main,
+            "focus",
+            capture,
+            log_to(&log, "main capture"),
+        );
+        MockDom::add_event_listener(
+            &p,
+            "focus",
+            ListenerOptions::default(),
+            log_to(&log, "p"),
+        );
+
+        // even events that don't bubble are captured
+        p.dispatch_event(Event::non_bubbling("focus"));
+        assert_eq!(*log.borrow(), ["main capture", "p"]);
+    }
+
+    #[test]
+    fn listener_options_are_respected() {
+        let count = Rc::new(Cell::new(0));
+        let el = MockDom::create_element(element::Div);
+        MockDom::add_event_listener(
+            &el,
+            "touchstart",
+            ListenerOptions {
+                once: true,
+                passive: true,
+                ..Default::default()
+            },
+            Box::new({
+                let count = Rc::clone(&count);
+                move |ev: Event| {
+                    ev.prevent_default();
+                    count.set(count.get() + 1);
+                }
+            }),
+        );
+
+        let ev = Event::new("touchstart");
+        el.dispatch_event(ev.clone());
+        assert!(!ev.default_prevented());
+        el.dispatch_event(Event::new("touchstart"));
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn undelegated_events_are_added_with_their_options() {
+        let count = Rc::new(Cell::new(0));
+        let el: HtmlElement<_, _, _, MockDom> =
+            button().on(event::undelegated(event::click).once(), {
+                let count = Rc::clone(&count);
+                move |_| count.set(count.get() + 1)
+            });
+        let el = el.build();
+        el.el.dispatch_event(Event::new("click"));
+        el.el.dispatch_event(Event::new("click"));
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn delegated_handlers_are_called_from_target_upward() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let main = MockDom::create_element(element::Main);
+        let button = MockDom::create_element(element::Button);
+        let span = MockDom::create_element(element::Span);
+        MockDom::insert_node(&main, button.as_ref(), None);
+        MockDom::insert_node(&button, span.as_ref(), None);
+        for (el, entry) in [(&main, "main"), (&button, "button")] {
+            MockDom::add_event_listener_delegated(
+                el,
+                "click".into(),
+                "$$$click".into(),
+                log_to(&log, entry),
+            );
+        }
+
+        span.dispatch_event(Event::new("click"));
+        assert_eq!(*log.borrow(), ["button", "main"]);
+
+        // disabled elements are skipped
+        log.borrow_mut().clear();
+        MockDom::set_attribute(&button, "disabled", "");
+        span.dispatch_event(Event::new("click"));
+        assert_eq!(*log.borrow(), ["main"]);
+
+        // as are all delegated handlers, if a native listener stops the event
+        log.borrow_mut().clear();
+        MockDom::add_event_listener(
+            &span,
+            "click",
+            ListenerOptions::default(),
+            Box::new(|ev: Event| ev.stop_propagation()),
+        );
+        span.dispatch_event(Event::new("click"));
+        assert!(log.borrow().is_empty());
+    }
+
+    #[test]
+    fn delegated_handlers_respect_stop_propagation() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let main = MockDom::create_element(element::Main);
+        MockDom::add_event_listener_delegated(
+            &main,
+            "click".into(),
+            "$$$click".into(),
+            log_to(&log, "main"),
+        );
+        let el: HtmlElement<_, _, _, MockDom> = button().on(event::click, {
+            let log = Rc::clone(&log);
+            move |_| {
+                log.borrow_mut().push("button".to_string());
+                Event::current().unwrap().stop_propagation();
+            }
+        });
+        let mut el = el.build();
+        el.el.mount(&main, None);
+
+        el.el.dispatch_event(Event::new("click"));
+        assert_eq!(*log.borrow(), ["button"]);
+    }
+
+    #[cfg(all(feature = "reaccy", feature = "tokio"))]
+    #[tokio::test]
+    async fn clicking_updates_reactive_view() {
+        use tachy_reaccy::prelude::*;
+
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let count = RwSignal::new(0);
+                let view: HtmlElement<_, _, _, MockDom> = button()
+                    .on(event::click, move |_| count.update(|n| *n += 1))
+                    .child(move || count.get());
+                let el = view.build();
+                assert_eq!(el.el.to_debug_html(), "<button>0</button>");
+
+                el.el.dispatch_event(Event::new("click"));
+                el.el.dispatch_event(Event::new("click"));
+                tokio::task::yield_now().await;
+
+                assert_eq!(count.get(), 2);
+                assert_eq!(el.el.to_debug_html(), "<button>2</button>");
+            })
+            .await;
+    }
+
+    #[test]
+    fn loaded_html_keeps_markers_as_placeholders() {
+        let html = "<main><p>Hello, <!>world!</p><!--comment--></main>";
+        let body = MockDom::load_html(html);
+        assert_eq!(body.to_debug_html(), format!("<body>{html}</body>"));
+
+        let main = MockDom::first_child(body.as_ref()).unwrap();
+        let p = MockDom::first_child(&main).unwrap();
+        let hello = MockDom::first_child(&p).unwrap();
+        let marker = MockDom::next_sibling(&hello).unwrap();
+        let world = MockDom::next_sibling(&marker).unwrap();
+        assert_eq!(Text::cast_from(hello).unwrap().data(), "Hello, ");
+        assert_eq!(Placeholder::cast_from(marker).unwrap().data(), "");
+        assert_eq!(Text::cast_from(world).unwrap().data(), "world!");
+
+        let comment = MockDom::next_sibling(&p).unwrap();
+        assert_eq!(Placeholder::cast_from(comment).unwrap().data(), "comment");
+    }
+
+    #[test]
+    fn loaded_html_swaps_out_of_order_fragments() {
+        let body = MockDom::load_html(
+            "<main>Before<!--s-1-o--><!>Loading...<!--s-1-c-->After</main>\
+             <template id=\"1-f\"><p>Outer<!--s-1-1-o-->Loading \
+             inner...<!--s-1-1-c--></p><!></template><script>swap()</script>\
+             <template id=\"1-1-f\"><!>Inner<!></template><script>swap()\
+             </script>",
+        );
+        assert_eq!(
+            body.to_debug_html(),
+            "<body><main>Before<p>Outer<!>Inner<!></p><!>After</main></body>"
+        );
+    }
+
+    #[test]
+    fn loaded_html_leaves_unresolved_fragments() {
+        let html = "<main><!--s-1-o-->Loading...<!--s-1-c--></main>";
+        let body = MockDom::load_html(html);
+        assert_eq!(body.to_debug_html(), format!("<body>{html}</body>"));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn hydrating_streamed_out_of_order_fragments() {
+        use crate::{
+            async_views::FutureViewExt,
+            hydration::{set_mismatch_behavior, MismatchBehavior},
+        };
+        use futures::StreamExt;
+        use std::time::Duration;
+
+        // the fragment is still pending on the server, but ready on the client
+        fn view(delay: Duration) -> impl RenderHtml<MockDom> {
+            let el: HtmlElement<_, _, _, MockDom> = main().child((
+                "Before",
+                async move {
+                    if !delay.is_zero() {
+                        tokio::time::sleep(delay).await;
+                    }
+                    "Suspended"
+                }
+                .suspend()
+                .with_fallback("Loading..."),
+                "After",
+            ));
+            el
+        }
+
+        let html = view(Duration::from_millis(50))
+            .to_html_stream_out_of_order()
+            .collect::<String>()
+            .await;
+        let body = MockDom::load_html(&html);
+        assert_eq!(
+            body.to_debug_html(),
+            "<body><main>Before<!>Suspended<!>After</main></body>"
+        );
+        set_mismatch_behavior(MismatchBehavior::Panic);
+        view(Duration::ZERO).hydrate_from::<true>(&body);
+    }
+
+    #[tokio::test]
+    async fn hydrating_streamed_in_order_views_needs_their_marker() {
+        use crate::{
+            async_views::FutureViewExt,
+            hydration::{set_mismatch_behavior, MismatchBehavior},
+        };
+        use futures::StreamExt;
+        use std::{
+            panic::{catch_unwind, AssertUnwindSafe},
+            time::Duration,
+        };
+
+        // the view is still pending on the server, but ready on the client
+        fn view(delay: Duration) -> impl RenderHtml<MockDom> {
+            let el: HtmlElement<_, _, _, MockDom> = main().child((
+                "Before",
+                async move {
+                    if !delay.is_zero() {
+                        tokio::time::sleep(delay).await;
+                    }
+                    "Suspended"
+                }
+                .suspend(),
+            ));
+            el
+        }
+
+        let html = view(Duration::from_millis(10))
+            .to_html_stream_in_order()
+            .collect::<String>()
+            .await;
+        // the marker after the resolved view is where `Either` expects to
+        // find its placeholder
+        assert_eq!(html, "<main>Before<!>Suspended<!></main>");
+        set_mismatch_behavior(MismatchBehavior::Panic);
+        view(Duration::ZERO).hydrate_from::<true>(&MockDom::load_html(&html));
+
+        if cfg!(debug_assertions) {
+            let body = MockDom::load_html("<main>Before<!>Suspended</main>");
+            let mismatch = catch_unwind(AssertUnwindSafe(|| {
+                view(Duration::ZERO).hydrate_from::<true>(&body);
+            }))
+            .unwrap_err();
+            assert_eq!(
+                mismatch.downcast_ref::<String>().unwrap(),
+                "hydration mismatch in main: expected <!>, but found nothing"
+            );
+        }
+    }
+
+    #[cfg(all(feature = "reaccy", feature = "tokio"))]
+    #[tokio::test]
+    async fn hydrating_loaded_html_updates_nodes_in_place() {
+        use tachy_reaccy::prelude::*;
+
+        tokio::task::LocalSet::new()
+            .run_until(async {
+                let count = RwSignal::new(0);
+                let view = move || -> HtmlElement<_, _, _, MockDom> {
+                    main().child((
+                        p().child(("Count: ", move || count.get())),
+                        span()
+                            .class(move || {
+                                if count.get() % 2 == 0 {
+                                    "even"
+                                } else {
+                                    "odd"
+                                }
+                            })
+                            .child("parity"),
+                    ))
+                };
+
+                let html = view().to_html();
+                assert_eq!(
+                    html,
+                    "<main><p>Count: <!>0</p><span \
+                     class=\"even\">parity</span></main>"
+                );
+                let body = MockDom::load_html(&html);
+                let main_el = MockDom::first_child(body.as_ref()).unwrap();
+                let p_el = MockDom::first_child(&main_el).unwrap();
+                let span_el = MockDom::next_sibling(&p_el).unwrap();
+                let count_text = MockDom::next_sibling(
+                    &MockDom::next_sibling(
+                        &MockDom::first_child(&p_el).unwrap(),
+                    )
+                    .unwrap(),
+                )
+                .unwrap();
+
+                let state = view().hydrate_from::<true>(&body);
+                assert!(node_eq(&state.el, &main_el));
+                assert_eq!(
+                    body.to_debug_html(),
+                    format!("<body>{html}</body>")
+                );
+
+                count.set(3);
+                tokio::task::yield_now().await;
+
+                assert_eq!(
+                    body.to_debug_html(),
+                    "<body><main><p>Count: <!>3</p><span \
+                     class=\"odd\">parity</span></main></body>"
+                );
+                // the same nodes were updated, rather than being replaced
+                let p_el2 = MockDom::first_child(&main_el).unwrap();
+                assert_eq!(p_el2, p_el);
+                assert_eq!(MockDom::next_sibling(&p_el2), Some(span_el));
+                assert_eq!(Text::cast_from(count_text).unwrap().data(), "3");
+            })
+            .await;
+    }
 }
@@ -1,6 +1,9 @@
-use crate::{html::element::CreateElement, spawner::Spawner, view::Mountable};
+use crate::{
+    html::{element::CreateElement, event::ListenerOptions},
+    spawner::Spawner,
+    view::Mountable,
+};
 use std::borrow::Cow;
-use wasm_bindgen::JsValue;
 
 pub mod dom;
 #[cfg(feature = "testing")]
@@ -89,6 +92,26 @@ pub trait Renderer: Sized {
     fn next_sibling(node: &Self::Node) -> Option<Self::Node>;
 
     fn log_node(node: &Self::Node);
+
+    /// Returns the tag name of the element, if the renderer can inspect it.
+    ///
+    /// This is used to detect hydration mismatches in debug builds.
+    fn tag_name(_el: &Self::Element) -> Option<String> {
+        None
+    }
+
+    /// Returns the value of an attribute of the element, if it is set and the
+    /// renderer can inspect it.
+    fn get_attribute(_el: &Self::Element, _name: &str) -> Option<String> {
+        None
+    }
+
+    /// Returns the content of the text node, if the renderer can inspect it.
+    ///
+    /// This is used to detect hydration mismatches in debug builds.
+    fn text_content(_node: &Self::Text) -> Option<String> {
+        None
+    }
 }
 
 /// Additional rendering behavior that applies only to DOM nodes.
@@ -99,16 +122,19 @@ pub trait DomRenderer: Renderer {
     type ClassList;
     /// The CSS styles for an element.
     type CssStyleDeclaration;
+    /// A value that can be set as a property of an element.
+    type PropertyValue;
 
     /// Sets a JavaScript object property on a DOM element.
-    fn set_property(el: &Self::Element, key: &str, value: &JsValue);
+    fn set_property(el: &Self::Element, key: &str, value: &Self::PropertyValue);
 
-    /// Adds an event listener to an element.
+    /// Adds an event listener to an element, with the given options.
     ///
     /// Returns a function to remove the listener.
     fn add_event_listener(
         el: &Self::Element,
         name: &str,
+        options: ListenerOptions,
         cb: Box<dyn FnMut(Self::Event)>,
     ) -> Box<dyn FnOnce(&Self::Element)>;
 
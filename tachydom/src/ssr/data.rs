@@ -0,0 +1,243 @@
+use super::{inline_script, StreamBuilder};
+use futures::{stream::SelectAll, Stream, StreamExt};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tachy_reaccy::{Owner, PinnedStream};
+
+/// A stream of HTML that includes the data serialized by resources on the
+/// server, created with [`StreamBuilder::with_resource_data`].
+///
+/// Each chunk of HTML after the shell is preceded by the `<script>` tags for
+/// any resources that resolved before it, including those created while
+/// rendering that chunk, so that a suspended fragment that reads a resource
+/// always arrives after that resource's data. The data for resources created
+/// while rendering the shell follows the shell, so that it stays at the start
+/// of the stream. Data for resources that resolve while no HTML is ready is
+/// sent as soon as it is available.
+pub struct StreamWithData {
+    html: StreamBuilder,
+    owner: Option<Owner>,
+    data: SelectAll<PinnedStream<String>>,
+    shell_sent: bool,
+    html_done: bool,
+}
+
+impl StreamBuilder {
+    /// Interleaves the data serialized by resources in the current
+    /// [`Owner`]'s shared context with this stream of HTML.
+    ///
+    /// Resources that are created while the stream is rendering are picked
+    /// up as well. If there is no shared context, the HTML is passed through
+    /// unchanged.
+    pub fn with_resource_data(self) -> StreamWithData {
+        StreamWithData {
+            html: self,
+            owner: Owner::current(),
+            data: SelectAll::new(),
+            shell_sent: false,
+            html_done: false,
+        }
+    }
+}
+
+impl StreamWithData {
+    // registers the data for any resources created since the last chunk
+    fn take_pending_data(&mut self) {
+        if let Some(owner) = &self.owner {
+            let pending = owner.with(|| {
+                Owner::shared_context().and_then(|sc| sc.pending_data())
+            });
+            if let Some(pending) = pending {
+                let nonce = self.html.nonce.clone();
+                self.data.push(Box::pin(
+                    pending.map(move |js| inline_script(&js, nonce.as_deref())),
+                ));
+            }
+        }
+    }
+
+    // collects every chunk of data that is ready now
+    fn ready_data(&mut self, cx: &mut Context<'_>, buf: &mut String) {
+        while let Poll::Ready(Some(chunk)) = self.data.poll_next_unpin(cx) {
+            buf.push_str(&chunk);
+        }
+    }
+}
+
+impl Stream for StreamWithData {
+    type Item = String;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = self.as_mut().get_mut();
+
+        if !this.html_done {
+            match this.html.poll_next_unpin(cx) {
+                Poll::Ready(Some(html)) => {
+                    // a fragment is only ready once the resources it reads
+                    // have resolved, so their data is ready by now as well,
+                    // even if they were created while rendering it
+                    this.take_pending_data();
+                    let mut buf = String::new();
+                    if this.shell_sent {
+                        this.ready_data(cx, &mut buf);
+                        buf.push_str(&html);
+                    } else {
+                        this.shell_sent = true;
+                        buf.push_str(&html);
+                        this.ready_data(cx, &mut buf);
+                    }
+                    return Poll::Ready(Some(buf));
+                }
+                Poll::Ready(None) => this.html_done = true,
+                Poll::Pending => {}
+            }
+        }
+
+        // send any data that resolves while we wait for the HTML
+        match this.data.poll_next_unpin(cx) {
+            Poll::Ready(Some(data)) => Poll::Ready(Some(data)),
+            Poll::Ready(None) if this.html_done => Poll::Ready(None),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod tests {
+    use crate::{
+        async_views::FutureViewExt,
+        html::element::{main, ElementChild, HtmlElement, Main},
+        renderer::dom::Dom,
+        view::RenderHtml,
+    };
+    use futures::StreamExt;
+    use std::time::Duration;
+    use tachy_reaccy::{shared_context::SharedContext, Owner, Root};
+    use tokio::time::sleep;
+
+    // registers a resource with the current shared context, as a `Resource`
+    // does on the server
+    fn resource(
+        sc: &dyn SharedContext,
+        fut: impl std::future::Future<Output = String> + Send + Sync + 'static,
+    ) {
+        let id = sc.next_id();
+        sc.write_async(id, Box::pin(fut));
+    }
+
+    #[tokio::test]
+    async fn data_for_a_fragment_comes_before_it() {
+        let Root(_owner, stream) = Root::global_ssr(|| {
+            let sc = Owner::shared_context().unwrap();
+            let el: HtmlElement<Main, _, _, Dom> = main().child(
+                async move {
+                    sleep(Duration::from_millis(10)).await;
+                    // the resource is created while rendering the fragment
+                    move || {
+                        resource(&*sc, async { String::from("1") });
+                        "Fragment"
+                    }
+                }
+                .suspend()
+                .with_fallback("Loading..."),
+            );
+            el.to_html_stream_out_of_order().with_resource_data()
+        });
+        let chunks = stream.collect::<Vec<_>>().await;
+
+        assert!(chunks[0].starts_with(
+            "<main><!--s-1-o-->Loading...<!--s-1-c--></main><script>"
+        ));
+        let fragment = chunks
+            .iter()
+            .find(|chunk| chunk.contains("<template id=\"1-f\">"))
+            .unwrap();
+        let data = fragment.find("__RESOLVED_RESOURCES[0] = \"1\";").unwrap();
+        assert!(data < fragment.find("<template").unwrap());
+    }
+
+    #[tokio::test]
+    async fn data_resolved_before_the_shell_follows_it() {
+        let Root(_owner, stream) = Root::global_ssr(|| {
+            resource(&*Owner::shared_context().unwrap(), async {
+                String::from("1")
+            });
+            let el: HtmlElement<Main, _, _, Dom> = main().child("Shell");
+            el.to_html_stream_out_of_order().with_resource_data()
+        });
+        let chunks = stream.collect::<Vec<_>>().await;
+
+        assert_eq!(
+            chunks,
+            ["<main>Shell</main><script>__RESOLVED_RESOURCES=[];\
+                 __PENDING_RESOURCES=[0,];__RESOURCE_RESOLVERS=[];</script>\
+                 <script>__RESOLVED_RESOURCES[0] = \"1\";\
+                 __RESOURCE_RESOLVERS[0]?.();</script>"]
+        );
+    }
+
+    #[tokio::test]
+    async fn data_is_sent_while_waiting_for_html() {
+        let Root(_owner, stream) = Root::global_ssr(|| {
+            resource(&*Owner::shared_context().unwrap(), async {
+                sleep(Duration::from_millis(10)).await;
+                String::from("1")
+            });
+            let el: HtmlElement<Main, _, _, Dom> = main().child(
+                async {
+                    sleep(Duration::from_millis(50)).await;
+                    "Fragment"
+                }
+                .suspend()
+                .with_fallback("Loading..."),
+            );
+            el.to_html_stream_out_of_order().with_resource_data()
+        });
+        let chunks = stream.collect::<Vec<_>>().await;
+
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[0].starts_with("<main><!--s-1-o-->"));
+        assert!(!chunks[0].contains("__RESOLVED_RESOURCES[0]"));
+        assert_eq!(
+            chunks[1],
+            "<script>__RESOLVED_RESOURCES[0] = \"1\";\
+             __RESOURCE_RESOLVERS[0]?.();</script>"
+        );
+        assert!(chunks[2].contains("<template id=\"1-f\">"));
+    }
+
+    #[tokio::test]
+    async fn every_script_carries_the_escaped_nonce() {
+        use tachy_reaccy::nonce::provide_nonce;
+
+        let Root(_owner, stream) = Root::global_ssr(|| {
+            provide_nonce("a\"><script>alert(1)</script>");
+            resource(&*Owner::shared_context().unwrap(), async {
+                String::from("1")
+            });
+            let el: HtmlElement<Main, _, _, Dom> = main().child(
+                async {
+                    sleep(Duration::from_millis(10)).await;
+                    "Fragment"
+                }
+                .suspend()
+                .with_fallback("Loading..."),
+            );
+            el.to_html_stream_out_of_order().with_resource_data()
+        });
+        let html = stream.collect::<String>().await;
+
+        let nonce = "<script nonce=\"a&quot;&gt;&lt;script&gt;alert(1)\
+                     &lt;/script&gt;\">";
+        // the resource data, the out-of-order runtime, and the swap
+        assert_eq!(html.matches(nonce).count(), 4);
+        assert_eq!(html.matches("<script").count(), 4);
+        assert!(html.contains(&format!("{nonce}__RESOLVED_RESOURCES[0] = ")));
+        assert!(html.contains(&format!("{nonce}__tachys_swap(\"1-\")")));
+    }
+}
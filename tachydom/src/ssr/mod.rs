@@ -1,7 +1,10 @@
 use crate::{
+    html::attribute::escape_attr,
     renderer::Renderer,
     view::{Position, PositionState, RenderHtml},
 };
+#[cfg(feature = "reaccy")]
+pub use data::*;
 use futures::Stream;
 use std::{
     collections::VecDeque,
@@ -12,6 +15,9 @@ use std::{
     task::{Context, Poll},
 };
 
+#[cfg(feature = "reaccy")]
+mod data;
+
 #[derive(Default)]
 pub struct StreamBuilder {
     sync_buf: String,
@@ -19,15 +25,66 @@ pub struct StreamBuilder {
     pending: Option<ChunkFuture>,
     pending_ooo: VecDeque<ChunkFuture>,
     id: Option<Vec<u16>>,
+    // whether the chunk in `pending` holds back the HTML before it
+    pending_blocks: bool,
+    partially_blocked: bool,
+    block_all: bool,
+    nonce: Option<String>,
+    // whether the chunk in `pending` is an out-of-order fragment
+    pending_out_of_order: bool,
+    ooo_runtime_sent: bool,
 }
 
 type PinnedFuture<T> = Pin<Box<dyn Future<Output = T> + Send + Sync>>;
 type ChunkFuture = PinnedFuture<VecDeque<StreamChunk>>;
 
 impl StreamBuilder {
+    /// Creates a new builder.
+    ///
+    /// With the `reaccy` feature, any nonce that has been provided through
+    /// context is added to every inline script the builder emits.
     pub fn new(id: Option<Vec<u16>>) -> Self {
         Self {
             id,
+            #[cfg(feature = "reaccy")]
+            nonce: tachy_reaccy::nonce::use_nonce().map(|n| n.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the nonce that is added to every inline script the builder emits,
+    /// for use with a Content Security Policy.
+    pub fn with_nonce(mut self, nonce: impl Into<String>) -> Self {
+        self.nonce = Some(nonce.into());
+        self
+    }
+
+    /// Resolves blocking suspense on the server, rendering it in place of its
+    /// fallback, rather than streaming it out of order.
+    pub fn partially_blocked(mut self) -> Self {
+        self.partially_blocked = true;
+        self
+    }
+
+    /// Whether blocking suspense is rendered in place on the server.
+    pub fn is_partially_blocked(&self) -> bool {
+        self.partially_blocked
+    }
+
+    /// Treats every async chunk as blocking, so that the stream yields the
+    /// whole document at once, apart from any out-of-order chunks.
+    pub fn block_all(mut self) -> Self {
+        self.block_all = true;
+        self
+    }
+
+    /// Creates a builder for the contents of a suspended child of the current
+    /// position, with the same rendering mode.
+    pub fn child_builder(&self) -> Self {
+        Self {
+            id: self.child_id(),
+            partially_blocked: self.partially_blocked,
+            nonce: self.nonce.clone(),
             ..Default::default()
         }
     }
@@ -142,30 +199,27 @@ impl StreamBuilder {
         Rndr::Node: Clone,
         Rndr::Element: Clone,
     {
-        let id = self.clone_id();
+        let mut subbuilder = StreamBuilder {
+            id: self.clone_id(),
+            partially_blocked: self.partially_blocked,
+            nonce: self.nonce.clone(),
+            ..Default::default()
+        };
         // copy so it's not updated by additional iterations
         // i.e., restart in the same position we were at when we suspended
         let mut position = *position;
 
+        // flush sync chunk, so that a blocking chunk knows what it holds back
+        let sync = mem::take(&mut self.sync_buf);
+        if !sync.is_empty() {
+            self.chunks.push_back(StreamChunk::Sync(sync));
+        }
         self.chunks.push_back(StreamChunk::OutOfOrder {
             should_block,
             chunks: Box::pin(async move {
                 let view = view.await;
 
-                let mut subbuilder = StreamBuilder::new(id);
-                let mut id = String::new();
-                if let Some(ids) = &subbuilder.id {
-                    for piece in ids {
-                        write!(&mut id, "{}-", piece).unwrap();
-                    }
-                }
-
-                subbuilder.sync_buf.reserve(591 + id.len()); // TODO size
-                subbuilder.sync_buf.push_str("<template id=\"");
-                subbuilder.sync_buf.push_str(&id);
-                subbuilder.sync_buf.push('f');
-                subbuilder.sync_buf.push_str("\">");
-
+                let id = subbuilder.open_fragment();
                 if let Some(id) = subbuilder.id.as_mut() {
                     id.push(0);
                 }
@@ -173,33 +227,167 @@ impl StreamBuilder {
                     &mut subbuilder,
                     &mut position,
                 );
+                subbuilder.close_fragment(&id);
 
-                subbuilder.sync_buf.push_str("<!></template>");
-
-                // TODO nonce
-                subbuilder.sync_buf.push_str("<script");
-                subbuilder.sync_buf.push_str(r#">(function() { let id = ""#);
-                subbuilder.sync_buf.push_str(&id);
-                subbuilder.sync_buf.push_str(
-                    "\";let open = undefined;let close = undefined;let walker \
-                     = document.createTreeWalker(document.body, \
-                     NodeFilter.SHOW_COMMENT);while(walker.nextNode()) \
-                     {if(walker.currentNode.textContent == `s-${id}o`){ \
-                     open=walker.currentNode; } else \
-                     if(walker.currentNode.textContent == `s-${id}c`) { close \
-                     = walker.currentNode;}}let range = new Range(); \
-                     range.setStartBefore(open); range.setEndBefore(close); \
-                     range.deleteContents(); let tpl = \
-                     document.getElementById(`${id}f`); \
-                     close.parentNode.insertBefore(tpl.content.\
-                     cloneNode(true), close);close.remove();})()",
-                );
-                subbuilder.sync_buf.push_str("</script>");
+                subbuilder.finish().take_chunks()
+            }),
+        });
+    }
+
+    /// Streams the HTML from `html` out of order once it resolves, replacing
+    /// the fallback most recently pushed with
+    /// [`push_fallback`](Self::push_fallback).
+    ///
+    /// Unlike [`push_async_out_of_order`](Self::push_async_out_of_order),
+    /// this takes HTML that has already been rendered, for example by
+    /// collecting a [`child_builder`](Self::child_builder) once all of its
+    /// chunks are ready.
+    pub fn push_resolved_out_of_order(
+        &mut self,
+        should_block: bool,
+        html: impl Future<Output = String> + Send + Sync + 'static,
+    ) {
+        let mut subbuilder = StreamBuilder {
+            id: self.clone_id(),
+            nonce: self.nonce.clone(),
+            ..Default::default()
+        };
+
+        // flush sync chunk, so that a blocking chunk knows what it holds back
+        let sync = mem::take(&mut self.sync_buf);
+        if !sync.is_empty() {
+            self.chunks.push_back(StreamChunk::Sync(sync));
+        }
+        self.chunks.push_back(StreamChunk::OutOfOrder {
+            should_block,
+            chunks: Box::pin(async move {
+                let html = html.await;
+
+                let id = subbuilder.open_fragment();
+                subbuilder.sync_buf.push_str(&html);
+                subbuilder.close_fragment(&id);
 
                 subbuilder.finish().take_chunks()
             }),
         });
     }
+
+    /// Whether any of the async chunks in this builder should block the HTML
+    /// around it.
+    pub fn is_blocking(&self) -> bool {
+        self.chunks.iter().any(|chunk| {
+            matches!(
+                chunk,
+                StreamChunk::Async {
+                    should_block: true,
+                    ..
+                } | StreamChunk::OutOfOrder {
+                    should_block: true,
+                    ..
+                }
+            )
+        })
+    }
+
+    /// Returns all of the HTML in this builder, if none of it is waiting on
+    /// an async chunk.
+    pub fn ready_html(&mut self) -> Option<String> {
+        let ready = self
+            .chunks
+            .iter()
+            .all(|chunk| matches!(chunk, StreamChunk::Sync(_)));
+        ready.then(|| {
+            let mut html = String::new();
+            for chunk in self.take_chunks() {
+                if let StreamChunk::Sync(chunk) = chunk {
+                    html.push_str(&chunk);
+                }
+            }
+            html
+        })
+    }
+
+    // opens the template for an out-of-order fragment, and returns its id
+    fn open_fragment(&mut self) -> String {
+        let mut id = String::new();
+        if let Some(ids) = &self.id {
+            for piece in ids {
+                write!(&mut id, "{}-", piece).unwrap();
+            }
+        }
+
+        self.sync_buf.reserve(64 + id.len() * 2);
+        self.sync_buf.push_str("<template id=\"");
+        self.sync_buf.push_str(&id);
+        self.sync_buf.push('f');
+        self.sync_buf.push_str("\">");
+        id
+    }
+
+    // closes the template for an out-of-order fragment, and swaps it in
+    fn close_fragment(&mut self, id: &str) {
+        self.sync_buf.push_str("<!></template>");
+
+        push_script_open(&mut self.sync_buf, self.nonce.as_deref());
+        self.sync_buf.push_str("__tachys_swap(\"");
+        self.sync_buf.push_str(id);
+        self.sync_buf.push_str("\")</script>");
+    }
+
+    // defines `__tachys_swap()`, which each out-of-order fragment calls to
+    // replace its fallback, before the first fragment that needs it
+    fn push_out_of_order_runtime(&mut self) {
+        if !self.ooo_runtime_sent {
+            self.ooo_runtime_sent = true;
+            push_script_open(&mut self.sync_buf, self.nonce.as_deref());
+            self.sync_buf.push_str(OUT_OF_ORDER_RUNTIME);
+            self.sync_buf.push_str("</script>");
+        }
+    }
+}
+
+const OUT_OF_ORDER_RUNTIME: &str = include_str!("out_of_order.js");
+
+// opens an inline script, with the nonce for this response if there is one
+fn push_script_open(buf: &mut String, nonce: Option<&str>) {
+    buf.push_str("<script");
+    if let Some(nonce) = nonce {
+        buf.push_str(" nonce=\"");
+        buf.push_str(&escape_attr(nonce));
+        buf.push('"');
+    }
+    buf.push('>');
+}
+
+/// Wraps the given JavaScript in an inline `<script>` tag, which carries the
+/// nonce if one is given.
+#[cfg(feature = "reaccy")]
+pub(crate) fn inline_script(js: &str, nonce: Option<&str>) -> String {
+    let mut buf = String::new();
+    push_script_open(&mut buf, nonce);
+    buf.push_str(js);
+    buf.push_str("</script>");
+    buf
+}
+
+/// A view that has been rendered with
+/// [`RenderHtml::to_html_async`](crate::view::RenderHtml::to_html_async).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolvedHtml {
+    /// The HTML for the view, with every suspended child resolved.
+    pub html: String,
+    /// `<script>` tags that send the data serialized by resources on the
+    /// server to the client.
+    pub resource_data: String,
+}
+
+impl ResolvedHtml {
+    /// Returns the HTML, followed by the resource data.
+    pub fn into_string(self) -> String {
+        let mut buf = self.html;
+        buf.push_str(&self.resource_data);
+        buf
+    }
 }
 
 impl Debug for StreamBuilder {
@@ -240,7 +428,6 @@ impl Debug for StreamChunk {
     }
 }
 
-// TODO handle should_block
 impl Stream for StreamBuilder {
     type Item = String;
 
@@ -248,86 +435,89 @@ impl Stream for StreamBuilder {
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        let mut this = self.as_mut();
-        let pending = this.pending.take();
-        if let Some(mut pending) = pending {
-            match pending.as_mut().poll(cx) {
-                Poll::Pending => {
-                    this.pending = Some(pending);
-                    Poll::Pending
-                }
-                Poll::Ready(chunks) => {
-                    for chunk in chunks.into_iter().rev() {
-                        this.chunks.push_front(chunk);
-                    }
-                    self.poll_next(cx)
-                }
-            }
-        } else {
-            let next_chunk = this.chunks.pop_front();
-            match next_chunk {
-                None => {
-                    let sync_buf = mem::take(&mut this.sync_buf);
-                    if sync_buf.is_empty() {
-                        // now, handle out-of-order chunks
-                        if let Some(mut pending) = this.pending_ooo.pop_front()
-                        {
-                            match pending.as_mut().poll(cx) {
-                                Poll::Ready(chunks) => {
-                                    for chunk in chunks.into_iter().rev() {
-                                        this.chunks.push_front(chunk);
-                                    }
-                                    self.poll_next(cx)
-                                }
-                                Poll::Pending => {
-                                    this.pending_ooo.push_back(pending);
-                                    Poll::Pending
-                                }
-                            }
-                        } else {
-                            Poll::Ready(None)
+        let this = self.as_mut().get_mut();
+        // the HTML that's ready to send is collected in `sync_buf`, which is
+        // no longer used for rendering once the stream has been built
+        loop {
+            if let Some(mut pending) = this.pending.take() {
+                match pending.as_mut().poll(cx) {
+                    Poll::Ready(chunks) => {
+                        if this.pending_out_of_order {
+                            this.push_out_of_order_runtime();
+                        }
+                        for chunk in chunks.into_iter().rev() {
+                            this.chunks.push_front(chunk);
                         }
-                    } else {
-                        Poll::Ready(Some(sync_buf))
+                        continue;
                     }
-                }
-                Some(StreamChunk::Sync(mut value)) => {
-                    loop {
-                        match this.chunks.pop_front() {
-                            None => break,
-                            Some(StreamChunk::Async {
-                                chunks,
-                                should_block,
-                            }) => {
-                                this.chunks.push_front(StreamChunk::Async {
-                                    chunks,
-                                    should_block,
-                                });
-                                break;
-                            }
-                            Some(StreamChunk::OutOfOrder {
-                                chunks, ..
-                            }) => {
-                                this.pending_ooo.push_back(chunks);
-                                break;
-                            }
-                            Some(StreamChunk::Sync(next)) => {
-                                value.push_str(&next);
-                            }
+                    Poll::Pending => {
+                        this.pending = Some(pending);
+                        // send what we have so far while we wait, unless this
+                        // chunk blocks it
+                        if !this.pending_blocks && !this.sync_buf.is_empty() {
+                            return Poll::Ready(Some(mem::take(
+                                &mut this.sync_buf,
+                            )));
                         }
+                        return Poll::Pending;
                     }
+                }
+            }
 
-                    let sync_buf = mem::take(&mut this.sync_buf);
-                    value.push_str(&sync_buf);
-                    Poll::Ready(Some(value))
+            match this.chunks.pop_front() {
+                Some(StreamChunk::Sync(value)) => {
+                    this.sync_buf.push_str(&value);
+                }
+                Some(StreamChunk::Async {
+                    chunks,
+                    should_block,
+                }) => {
+                    this.pending = Some(chunks);
+                    this.pending_blocks = should_block || this.block_all;
+                    this.pending_out_of_order = false;
                 }
-                Some(StreamChunk::Async { chunks, .. }) => {
+                // blocking out-of-order chunks are resolved in place, so the
+                // HTML around them is held back until they're ready
+                Some(StreamChunk::OutOfOrder {
+                    chunks,
+                    should_block: true,
+                }) => {
                     this.pending = Some(chunks);
-                    self.poll_next(cx)
+                    this.pending_blocks = true;
+                    this.pending_out_of_order = true;
                 }
                 Some(StreamChunk::OutOfOrder { chunks, .. }) => {
                     this.pending_ooo.push_back(chunks);
-                    self.poll_next(cx)
+                }
+                None => {
+                    if !this.sync_buf.is_empty() {
+                        return Poll::Ready(Some(mem::take(
+                            &mut this.sync_buf,
+                        )));
+                    }
+
+                    // now, handle out-of-order chunks, in whatever order they
+                    // resolve
+                    if this.pending_ooo.is_empty() {
+                        return Poll::Ready(None);
+                    }
+                    let ready =
+                        this.pending_ooo.iter_mut().enumerate().find_map(
+                            |(idx, pending)| match pending.as_mut().poll(cx) {
+                                Poll::Ready(chunks) => Some((idx, chunks)),
+                                Poll::Pending => None,
+                            },
+                        );
+                    match ready {
+                        Some((idx, chunks)) => {
+                            this.pending_ooo.remove(idx);
+                            this.push_out_of_order_runtime();
+                            for chunk in chunks.into_iter().rev() {
+                                this.chunks.push_front(chunk);
+                            }
+                        }
+                        None => return Poll::Pending,
+                    }
                 }
             }
         }
@@ -394,7 +584,7 @@ mod tests {
             );
 
         assert_eq!(stream.next().await.unwrap(), "Before Suspense");
-        assert_eq!(stream.next().await.unwrap(), "<!>Suspended");
+        assert_eq!(stream.next().await.unwrap(), "<!>Suspended<!>");
         assert!(stream.next().await.is_none());
     }
 
@@ -411,7 +601,7 @@ mod tests {
         let mut stream = el.to_html_stream_in_order();
 
         assert_eq!(stream.next().await.unwrap(), "<p>Before Suspense");
-        assert_eq!(stream.next().await.unwrap(), "<!>Suspended</p>");
+        assert_eq!(stream.next().await.unwrap(), "<!>Suspended<!></p>");
         assert!(stream.next().await.is_none());
     }
 
@@ -438,7 +628,7 @@ mod tests {
         assert_eq!(stream.next().await.unwrap(), "<p>Before inner Suspense");
         assert_eq!(
             stream.next().await.unwrap(),
-            "<!>Inner Suspense</p></main>"
+            "<!>Inner Suspense<!></p><!></main>"
         );
     }
 
@@ -475,22 +665,13 @@ mod tests {
             stream.next().await.unwrap(),
             "<!--s-1-o-->Loading...<!--s-1-c-->"
         );
-        assert_eq!(
-            stream.next().await.unwrap(),
-            "<template id=\"1-f\">Suspended</template><script>(function() { \
-             let id = \"1-\";let open = undefined;let close = undefined;let \
-             walker = document.createTreeWalker(document.body, \
-             NodeFilter.SHOW_COMMENT);while(walker.nextNode()) \
-             {if(walker.currentNode.textContent == `s-${id}o`){ \
-             open=walker.currentNode; } else \
-             if(walker.currentNode.textContent == `s-${id}c`) { close = \
-             walker.currentNode;}}let range = new Range(); \
-             range.setStartAfter(open); range.setEndBefore(close); \
-             range.deleteContents(); let tpl = \
-             document.getElementById(`${id}f`); \
-             close.parentNode.insertBefore(tpl.content.cloneNode(true), \
-             close);})()</script>"
-        );
+        let html = stream.next().await.unwrap();
+        assert!(html.starts_with("<script>(function () {"));
+        assert!(html.ends_with(
+            "</script><template id=\"1-f\">Suspended<!></template>\
+             <script>__tachys_swap(\"1-\")</script>"
+        ));
+        assert!(stream.next().await.is_none());
     }
 
     #[tokio::test]
@@ -549,12 +730,225 @@ mod tests {
             "<p>Before inner Suspense<!--s-1-1-o--><!>Loading \
              Inner...<!--s-1-1-c-->After inner Suspense</p>"
         ));
-        assert!(loading_inner.contains("let id = \"1-\";"));
+        assert!(loading_inner.contains("window.__tachys_swap"));
+        assert!(
+            loading_inner.ends_with("<script>__tachys_swap(\"1-\")</script>")
+        );
 
+        // the runtime is only sent once
         let inner = stream.next().await.unwrap();
-        assert!(inner.contains("Inner Suspense"));
-        assert!(inner.contains("let id = \"1-1-\";"));
+        assert_eq!(
+            inner,
+            "<template id=\"1-1-f\"><!>Inner Suspense<!></template>\
+             <script>__tachys_swap(\"1-1-\")</script>"
+        );
 
         assert!(stream.next().await.is_none());
     }
+
+    #[tokio::test]
+    async fn async_html_resolves_every_suspended_child() {
+        let el: HtmlElement<_, _, _, Dom> = main().child((
+            "Before Suspense",
+            async {
+                sleep(Duration::from_millis(100)).await;
+                p().child((
+                    "Before inner Suspense",
+                    async {
+                        sleep(Duration::from_millis(100)).await;
+                        "Inner Suspense"
+                    }
+                    .suspend()
+                    .with_fallback("Loading Inner..."),
+                ))
+            }
+            .suspend()
+            .with_fallback("Loading..."),
+            "After Suspense",
+        ));
+
+        let resolved = el.to_html_async().await;
+        assert_eq!(
+            resolved.html,
+            "<main>Before Suspense<p>Before inner Suspense<!>Inner \
+             Suspense<!></p><!>After Suspense</main>"
+        );
+        assert_eq!(resolved.resource_data, "");
+    }
+
+    #[cfg(feature = "reaccy")]
+    #[tokio::test]
+    async fn async_html_includes_data_for_resources_read_while_rendering() {
+        use tachy_reaccy::{Owner, Root};
+
+        let Root(_owner, resolved) = Root::global_ssr(|| {
+            let sc = Owner::shared_context().unwrap();
+            let el: HtmlElement<_, _, _, Dom> = main().child(
+                async move {
+                    // the resource is created, and resolves, while the
+                    // suspended child is rendering
+                    let id = sc.next_id();
+                    sc.write_async(
+                        id,
+                        Box::pin(async {
+                            sleep(Duration::from_millis(10)).await;
+                            String::from("1")
+                        }),
+                    );
+                    sleep(Duration::from_millis(50)).await;
+                    "Suspended"
+                }
+                .suspend()
+                .with_fallback("Loading..."),
+            );
+            el.to_html_async()
+        });
+        let resolved = resolved.await;
+
+        assert_eq!(resolved.html, "<main>Suspended<!></main>");
+        assert!(resolved.resource_data.contains(
+            "<script>__RESOLVED_RESOURCES[0] = \"1\";\
+             __RESOURCE_RESOLVERS[0]?.();</script>"
+        ));
+    }
+
+    #[tokio::test]
+    async fn in_order_blocking_async_block_holds_back_siblings() {
+        let el: HtmlElement<_, _, _, Dom> = p().child((
+            "Before Suspense",
+            async {
+                sleep(Duration::from_millis(250)).await;
+                "Suspended"
+            }
+            .suspend()
+            .blocking(),
+            "After Suspense",
+        ));
+        let mut stream = el.to_html_stream_in_order();
+
+        assert_eq!(
+            stream.next().await.unwrap(),
+            "<p>Before Suspense<!>Suspended<!>After Suspense</p>"
+        );
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn out_of_order_blocking_async_block_holds_back_shell() {
+        let el: HtmlElement<_, _, _, Dom> = p().child((
+            "Before Suspense",
+            async {
+                sleep(Duration::from_millis(250)).await;
+                "Suspended"
+            }
+            .suspend()
+            .with_fallback("Loading...")
+            .blocking(),
+            "After Suspense",
+        ));
+        let mut stream = el.to_html_stream_out_of_order();
+
+        // the fragment is still swapped in by the script, but it's sent
+        // along with the shell
+        let html = stream.next().await.unwrap();
+        assert!(html.starts_with(
+            "<p>Before Suspense<!--s-1-o--><!>Loading...<!--s-1-c--><script>"
+        ));
+        assert!(html.ends_with(
+            "</script><template id=\"1-f\"><!>Suspended<!></template>\
+             <script>__tachys_swap(\"1-\")</script>After Suspense</p>"
+        ));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[cfg(feature = "reaccy")]
+    #[tokio::test]
+    async fn out_of_order_script_carries_nonce_from_context() {
+        use tachy_reaccy::{nonce::provide_nonce, Root};
+
+        let Root(_owner, mut stream) = Root::new(|| {
+            provide_nonce("abc123");
+            let el: HtmlElement<_, _, _, Dom> = p().child(
+                async {
+                    sleep(Duration::from_millis(250)).await;
+                    "Suspended"
+                }
+                .suspend()
+                .with_fallback("Loading..."),
+            );
+            el.to_html_stream_out_of_order()
+        });
+
+        let html = stream.next().await.unwrap();
+        assert!(!html.contains("<script"));
+        let html = stream.next().await.unwrap();
+        assert!(html.starts_with("<script nonce=\"abc123\">(function () {"));
+        assert!(html.contains("</template><script nonce=\"abc123\">"));
+    }
+
+    #[tokio::test]
+    async fn partially_blocked_renders_blocking_async_block_in_place() {
+        let el: HtmlElement<_, _, _, Dom> = main().child((
+            "Before Suspense",
+            async {
+                sleep(Duration::from_millis(250)).await;
+                "Blocking"
+            }
+            .suspend()
+            .with_fallback("Loading...")
+            .blocking(),
+            async {
+                sleep(Duration::from_millis(250)).await;
+                "Not blocking"
+            }
+            .suspend()
+            .with_fallback("Loading..."),
+        ));
+        let mut stream = el.to_html_stream_partially_blocked();
+
+        assert_eq!(
+            stream.next().await.unwrap(),
+            "<main>Before Suspense<!>Blocking<!><!--s-2-o-->Loading...\
+             <!--s-2-c--></main>"
+        );
+        let html = stream.next().await.unwrap();
+        assert!(html.ends_with(
+            "<template id=\"2-f\">Not blocking<!></template><script>\
+             __tachys_swap(\"2-\")</script>"
+        ));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn partially_blocked_nested_suspense_gets_unique_ids() {
+        let el: HtmlElement<_, _, _, Dom> = main().child((
+            async {
+                sleep(Duration::from_millis(100)).await;
+                async {
+                    sleep(Duration::from_millis(100)).await;
+                    "Inner"
+                }
+                .suspend()
+                .with_fallback("Loading inner...")
+            }
+            .suspend()
+            .blocking(),
+            async {
+                sleep(Duration::from_millis(100)).await;
+                "Sibling"
+            }
+            .suspend()
+            .with_fallback("Loading sibling..."),
+        ));
+        let mut stream = el.to_html_stream_partially_blocked();
+
+        let shell = stream.next().await.unwrap();
+        assert!(shell.contains("<!--s-1-1-o-->Loading inner..."));
+        assert!(shell.contains("<!--s-2-o-->Loading sibling..."));
+        let rest = [stream.next().await.unwrap(), stream.next().await.unwrap()]
+            .concat();
+        assert!(rest.contains("<template id=\"1-1-f\">Inner<!></template>"));
+        assert!(rest.contains("<template id=\"2-f\">Sibling<!></template>"));
+        assert!(stream.next().await.is_none());
+    }
 }
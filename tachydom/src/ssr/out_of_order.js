@@ -0,0 +1,44 @@
+(function () {
+	// finds the markers around a suspense boundary's fallback
+	function markers(root, id) {
+		let open, close;
+		const walker = document.createTreeWalker(root, NodeFilter.SHOW_COMMENT);
+		while (walker.nextNode()) {
+			const data = walker.currentNode.data;
+			if (data === `s-${id}o`) {
+				open = walker.currentNode;
+			} else if (data === `s-${id}c`) {
+				close = walker.currentNode;
+				break;
+			}
+		}
+		return open && close ? [open, close] : undefined;
+	}
+
+	window.__tachys_swap = function (id) {
+		document.currentScript?.remove();
+		const tpl = document.getElementById(`${id}f`);
+		if (!tpl) return;
+		let found = markers(document.body, id);
+		// a nested boundary may still be inside a fragment that hasn't been
+		// swapped in yet
+		if (!found) {
+			for (const other of document.querySelectorAll("template")) {
+				found = markers(other.content, id);
+				if (found) break;
+			}
+		}
+		// if the boundary is gone, there's nothing left to replace
+		if (found) {
+			const [open, close] = found;
+			const range = new Range();
+			range.setStartBefore(open);
+			range.setEndAfter(close);
+			range.deleteContents();
+			range.insertNode(document.importNode(tpl.content, true));
+		}
+		tpl.remove();
+	};
+
+	document.currentScript?.remove();
+})()
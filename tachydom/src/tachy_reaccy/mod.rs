@@ -14,6 +14,7 @@ use tachy_reaccy::{async_signal::ScopedFuture, render_effect::RenderEffect};
 
 mod class;
 pub mod node_ref;
+mod nonce;
 mod style;
 
 impl<F, V> ToTemplate for F
@@ -169,6 +170,11 @@ where
         value.to_html_async_with_buf::<OUT_OF_ORDER>(buf, position);
     }
 
+    fn to_raw_text_with_buf(mut self, buf: &mut String) {
+        let value = self();
+        value.to_raw_text_with_buf(buf);
+    }
+
     fn hydrate<const FROM_SERVER: bool>(
         mut self,
         cursor: &Cursor<R>,
@@ -221,10 +227,17 @@ where
 // Extends to track suspense
 impl<const TRANSITION: bool, Fal, Fut> Suspend<TRANSITION, Fal, Fut> {
     pub fn track(self) -> Suspend<TRANSITION, Fal, ScopedFuture<Fut>> {
-        let Suspend { fallback, fut } = self;
+        let Suspend {
+            fallback,
+            fut,
+            should_block,
+            transition,
+        } = self;
         Suspend {
             fallback,
             fut: ScopedFuture::new(fut),
+            should_block,
+            transition,
         }
     }
 }
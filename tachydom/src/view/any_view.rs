@@ -1,8 +1,5 @@
 use super::{Mountable, Position, PositionState, Render, RenderHtml};
-use crate::{
-    hydration::Cursor,
-    renderer::{CastFrom, Renderer},
-};
+use crate::{hydration::Cursor, renderer::Renderer, ssr::StreamBuilder};
 use std::{
     any::{Any, TypeId},
     marker::PhantomData,
@@ -15,6 +12,8 @@ where
     type_id: TypeId,
     value: Box<dyn Any>,
     to_html: fn(Box<dyn Any>, &mut String, &mut Position),
+    to_html_async: fn(Box<dyn Any>, &mut StreamBuilder, &mut Position),
+    to_html_async_ooo: fn(Box<dyn Any>, &mut StreamBuilder, &mut Position),
     build: fn(Box<dyn Any>) -> AnyViewState<R>,
     rebuild: fn(TypeId, Box<dyn Any>, &mut AnyViewState<R>),
     #[allow(clippy::type_complexity)]
@@ -90,6 +89,25 @@ where
     state.insert_before_this(parent, child)
 }
 
+fn erased_to_html_async<R, T, const OUT_OF_ORDER: bool>(
+    value: Box<dyn Any>,
+    buf: &mut StreamBuilder,
+    position: &mut Position,
+) where
+    T: RenderHtml<R> + 'static,
+    R: Renderer,
+    R::Node: Clone,
+    R::Element: Clone,
+{
+    let value = value
+        .downcast::<T>()
+        .expect("AnyView::to_html_async could not be downcast");
+    value.to_html_async_with_buf::<OUT_OF_ORDER>(buf, position);
+    // insert marker node
+    buf.push_sync("<!>");
+    *position = Position::NextChild;
+}
+
 impl<T, R> IntoAny<R> for T
 where
     T: RenderHtml<R> + 'static,
@@ -141,8 +159,7 @@ where
 
                 // get placeholder node
                 cursor.sibling();
-                let placeholder = R::Placeholder::cast_from(cursor.current())
-                    .expect("should be a placeholder node");
+                let placeholder = cursor.expect_placeholder();
                 position.set(Position::NextChild);
 
                 AnyViewState {
@@ -161,13 +178,12 @@ where
              position: &PositionState| {
                 let value = value
                     .downcast::<T>()
-                    .expect("AnyView::hydrate_from_server couldn't downcast");
-                let state = Box::new(value.hydrate::<true>(cursor, position));
+                    .expect("AnyView::hydrate_from_template couldn't downcast");
+                let state = Box::new(value.hydrate::<false>(cursor, position));
 
                 // get placeholder node
                 cursor.sibling();
-                let placeholder = R::Placeholder::cast_from(cursor.current())
-                    .expect("should be a placeholder node");
+                let placeholder = cursor.expect_placeholder();
                 position.set(Position::NextChild);
 
                 AnyViewState {
@@ -211,6 +227,8 @@ where
             type_id: TypeId::of::<T>(),
             value,
             to_html,
+            to_html_async: erased_to_html_async::<R, T, false>,
+            to_html_async_ooo: erased_to_html_async::<R, T, true>,
             build,
             rebuild,
             hydrate_from_server,
@@ -246,6 +264,20 @@ where
         (self.to_html)(self.value, buf, position);
     }
 
+    fn to_html_async_with_buf<const OUT_OF_ORDER: bool>(
+        self,
+        buf: &mut StreamBuilder,
+        position: &mut Position,
+    ) where
+        Self: Sized,
+    {
+        if OUT_OF_ORDER {
+            (self.to_html_async_ooo)(self.value, buf, position);
+        } else {
+            (self.to_html_async)(self.value, buf, position);
+        }
+    }
+
     fn hydrate<const FROM_SERVER: bool>(
         self,
         cursor: &Cursor<R>,
@@ -279,27 +311,87 @@ where
         (self.insert_before_this)(self, parent, child)
     }
 }
-/*
 #[cfg(test)]
 mod tests {
-    use super::IntoAny;
+    use super::{AnyView, IntoAny};
     use crate::{
-        html::element::{p, span},
+        html::element::{p, span, ElementChild},
         renderer::mock_dom::MockDom,
-        view::{any_view::AnyView, RenderHtml},
+        view::RenderHtml,
     };
 
     #[test]
     fn should_handle_html_creation() {
         let x = 1;
-        let mut buf = String::new();
         let view: AnyView<MockDom> = if x == 0 {
-            p((), "foo").into_any()
+            p().child("foo").into_any()
         } else {
-            span((), "bar").into_any()
+            span().child("bar").into_any()
         };
-        view.to_html(&mut buf, &Default::default());
-        assert_eq!(buf, "<span>bar</span><!>");
+        assert_eq!(view.to_html(), "<span>bar</span><!>");
+    }
+
+    #[test]
+    fn erased_view_hydrates_from_template() {
+        use crate::html::attribute::global::GlobalAttributes;
+
+        // a template has the nodes, but not the text or attributes
+        let body = MockDom::load_html("<p> </p><!>");
+        let view: AnyView<MockDom> = p().id("foo").child("Hello").into_any();
+        let _state = view.hydrate_from::<false>(&body);
+
+        assert_eq!(
+            body.to_debug_html(),
+            "<body><p id=\"foo\">Hello</p><!></body>"
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn erased_suspense_streams_in_order() {
+        use crate::async_views::FutureViewExt;
+        use futures::StreamExt;
+        use std::time::Duration;
+        use tokio::time::sleep;
+
+        let view: AnyView<MockDom> = p()
+            .child((
+                "Before",
+                async {
+                    sleep(Duration::from_millis(10)).await;
+                    "Suspended"
+                }
+                .suspend(),
+            ))
+            .into_any();
+        let mut stream = view.to_html_stream_in_order();
+
+        assert_eq!(stream.next().await.unwrap(), "<p>Before");
+        assert_eq!(stream.next().await.unwrap(), "<!>Suspended<!></p><!>");
+        assert!(stream.next().await.is_none());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn erased_suspense_streams_out_of_order() {
+        use crate::async_views::FutureViewExt;
+        use futures::StreamExt;
+        use std::time::Duration;
+        use tokio::time::sleep;
+
+        let view: AnyView<MockDom> = p()
+            .child(
+                async {
+                    sleep(Duration::from_millis(10)).await;
+                    "Suspended"
+                }
+                .suspend()
+                .with_fallback("Loading"),
+            )
+            .into_any();
+        let html = view.to_html_stream_out_of_order().collect::<String>().await;
+
+        assert!(html.starts_with("<p><!--s-1-o-->Loading<!--s-1-c--></p><!>"));
+        assert!(html.contains("<template id=\"1-f\">Suspended<!></template>"));
     }
 }
- */
@@ -1,7 +1,8 @@
 use super::{Mountable, Position, PositionState, Render, RenderHtml};
 use crate::{
+    html::{attribute::Attribute, element::AddAnyAttr},
     hydration::Cursor,
-    renderer::{CastFrom, Renderer},
+    renderer::Renderer,
     ssr::StreamBuilder,
 };
 
@@ -125,6 +126,13 @@ where
         *position = Position::NextChild;
     }
 
+    fn to_raw_text_with_buf(self, buf: &mut String) {
+        match self {
+            Either::Left(left) => left.to_raw_text_with_buf(buf),
+            Either::Right(right) => right.to_raw_text_with_buf(buf),
+        }
+    }
+
     fn to_html_async_with_buf<const OUT_OF_ORDER: bool>(
         self,
         buf: &mut StreamBuilder,
@@ -158,13 +166,34 @@ where
             }
         };
         cursor.sibling();
-        let marker = cursor.current().to_owned();
-        let marker = Rndr::Placeholder::cast_from(marker).unwrap();
+        let marker = cursor.expect_placeholder();
         position.set(Position::NextChild);
         EitherState { state, marker }
     }
 }
 
+impl<A, B, Rndr> AddAnyAttr<Rndr> for Either<A, B>
+where
+    A: AddAnyAttr<Rndr>,
+    B: AddAnyAttr<Rndr>,
+    Rndr: Renderer,
+    Rndr::Node: Clone,
+    Rndr::Element: Clone,
+{
+    type Output<SomeNewAttr: Attribute<Rndr>> =
+        Either<A::Output<SomeNewAttr>, B::Output<SomeNewAttr>>;
+
+    fn add_any_attr<NewAttr: Attribute<Rndr>>(
+        self,
+        attr: NewAttr,
+    ) -> Self::Output<NewAttr> {
+        match self {
+            Either::Left(a) => Either::Left(a.add_any_attr(attr)),
+            Either::Right(b) => Either::Right(b.add_any_attr(attr)),
+        }
+    }
+}
+
 const fn min_usize(vals: &[usize]) -> usize {
     let mut min = usize::MAX;
     let len = vals.len();
@@ -283,6 +312,12 @@ macro_rules! tuples {
                     *position = Position::NextChild;
                 }
 
+                fn to_raw_text_with_buf(self, buf: &mut String) {
+                    match self {
+                        $([<EitherOf $num>]::$ty(this) => this.to_raw_text_with_buf(buf),)*
+                    }
+                }
+
                 fn to_html_async_with_buf<const OUT_OF_ORDER: bool>(
                     self,
                     buf: &mut StreamBuilder,
@@ -306,8 +341,7 @@ macro_rules! tuples {
                         $([<EitherOf $num>]::$ty(this) => [<EitherOf $num>]::$ty(this.hydrate::<FROM_SERVER>(cursor, position)),)*
                     };
                     cursor.sibling();
-                    let marker = cursor.current().to_owned();
-                    let marker = Rndr::Placeholder::cast_from(marker).unwrap();
+                    let marker = cursor.expect_placeholder();
                     position.set(Position::NextChild);
                     Self::State { marker, state }
                 }
@@ -1,9 +1,5 @@
 use super::{Mountable, Position, PositionState, Render, RenderHtml};
-use crate::{
-    hydration::Cursor,
-    renderer::{CastFrom, Renderer},
-    ssr::StreamBuilder,
-};
+use crate::{hydration::Cursor, renderer::Renderer, ssr::StreamBuilder};
 use itertools::Itertools;
 
 impl<T, R> Render<R> for Option<T>
@@ -76,6 +72,12 @@ where
         *position = Position::NextChild;
     }
 
+    fn to_raw_text_with_buf(self, buf: &mut String) {
+        if let Some(value) = self {
+            value.to_raw_text_with_buf(buf);
+        }
+    }
+
     fn hydrate<const FROM_SERVER: bool>(
         self,
         cursor: &Cursor<R>,
@@ -90,8 +92,7 @@ where
         } else {
             cursor.sibling();
         }
-        let placeholder = cursor.current().to_owned();
-        let placeholder = R::Placeholder::cast_from(placeholder).unwrap();
+        let placeholder = cursor.expect_placeholder();
         position.set(Position::NextChild);
 
         OptionState { placeholder, state }
@@ -298,6 +299,12 @@ where
         }
     }
 
+    fn to_raw_text_with_buf(self, buf: &mut String) {
+        for child in self {
+            child.to_raw_text_with_buf(buf);
+        }
+    }
+
     fn hydrate<const FROM_SERVER: bool>(
         self,
         cursor: &Cursor<R>,
@@ -1,4 +1,9 @@
-use crate::{hydration::Cursor, renderer::Renderer, ssr::StreamBuilder};
+use crate::{
+    hydration::Cursor,
+    renderer::Renderer,
+    ssr::{ResolvedHtml, StreamBuilder},
+};
+use futures::{Future, StreamExt};
 use parking_lot::RwLock;
 use std::sync::Arc;
 
@@ -8,6 +13,7 @@ pub mod error_boundary;
 pub mod iterators;
 pub mod keyed;
 mod primitives;
+pub mod raw_html;
 #[cfg(feature = "nightly")]
 pub mod static_types;
 pub mod strings;
@@ -143,6 +149,57 @@ where
         builder.finish()
     }
 
+    /// Renders a view to an out-of-order stream of HTML, in which blocking
+    /// suspense is rendered in place on the server before the HTML around it
+    /// is sent.
+    fn to_html_stream_partially_blocked(self) -> StreamBuilder
+    where
+        Self: Sized,
+    {
+        let mut builder = StreamBuilder::new(Some(vec![0])).partially_blocked();
+        self.to_html_async_with_buf::<true>(
+            &mut builder,
+            &mut Position::FirstChild,
+        );
+        builder.finish()
+    }
+
+    /// Renders a view to a complete HTML string, waiting for every suspended
+    /// child to resolve rather than rendering its fallback.
+    ///
+    /// With the `reaccy` feature, this also collects the data serialized by
+    /// any resources that were read while rendering, so that it can be sent
+    /// to the client along with the HTML.
+    fn to_html_async(self) -> impl Future<Output = ResolvedHtml> + Send
+    where
+        Self: Sized,
+    {
+        #[cfg(feature = "reaccy")]
+        let shared_context = tachy_reaccy::Owner::shared_context();
+        #[cfg(feature = "reaccy")]
+        let nonce = tachy_reaccy::nonce::use_nonce();
+        let stream = self.to_html_stream_in_order().block_all();
+
+        async move {
+            let html = stream.collect::<String>().await;
+            #[allow(unused_mut)]
+            let mut resource_data = String::new();
+            #[cfg(feature = "reaccy")]
+            if let Some(pending) =
+                shared_context.and_then(|sc| sc.pending_data())
+            {
+                resource_data = pending
+                    .map(|js| crate::ssr::inline_script(&js, nonce.as_deref()))
+                    .collect::<String>()
+                    .await;
+            }
+            ResolvedHtml {
+                html,
+                resource_data,
+            }
+        }
+    }
+
     /// Renders a view to an HTML string, asynchronously.
     /* fn to_html_stream(self) -> impl Stream<Item = String>
     where
@@ -179,6 +236,19 @@ where
         buf.with_buf(|buf| self.to_html_with_buf(buf, position));
     }
 
+    /// Renders a view as the raw text content of a `<script>` or `<style>`
+    /// element, which the browser does not parse as HTML.
+    ///
+    /// Text views write their text as it is, without escaping it and without
+    /// the comments that separate adjacent text nodes. Any other view is
+    /// rendered as HTML.
+    fn to_raw_text_with_buf(self, buf: &mut String)
+    where
+        Self: Sized,
+    {
+        self.to_html_with_buf(buf, &mut Position::FirstChild);
+    }
+
     /// Makes a set of DOM nodes rendered from HTML interactive.
     ///
     /// If `FROM_SERVER` is `true`, this HTML was rendered using [`RenderHtml::to_html`]
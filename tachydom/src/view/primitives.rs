@@ -1,5 +1,6 @@
 use super::{
-    InfallibleRender, Mountable, Position, PositionState, Render, RenderHtml,
+    strings::EscapedText, InfallibleRender, Mountable, Position, PositionState,
+    Render, RenderHtml,
 };
 use crate::{
     hydration::Cursor,
@@ -77,10 +78,14 @@ macro_rules! render_primitive {
 					if matches!(position, Position::NextChildAfterText) {
 						buf.push_str("<!>")
 					}
-					write!(buf, "{}", self);
+					_ = write!(EscapedText(buf), "{}", self);
 					*position = Position::NextChildAfterText;
 				}
 
+				fn to_raw_text_with_buf(self, buf: &mut String) {
+					_ = write!(buf, "{}", self);
+				}
+
 				fn hydrate<const FROM_SERVER: bool>(
 					self,
 					cursor: &Cursor<R>,
@@ -97,13 +102,19 @@ macro_rules! render_primitive {
 						cursor.sibling();
 					}
 
-					let node = cursor.current();
-					let node = R::Text::cast_from(node)
-						.expect("couldn't cast text node from node");
-
-					if !FROM_SERVER {
-						R::set_text(&node, &self.to_string());
-					}
+					// the text is only needed to check for mismatches in debug
+					// builds, or to fill in a node that wasn't server-rendered
+					let node = if cfg!(debug_assertions) || !FROM_SERVER {
+						let text = self.to_string();
+						let node = cursor.expect_text::<FROM_SERVER>(&text);
+						if !FROM_SERVER {
+							R::set_text(&node, &text);
+						}
+						node
+					} else {
+						R::Text::cast_from(cursor.current())
+							.expect("couldn't cast text node from node")
+					};
 					position.set(Position::NextChildAfterText);
 
 					[<$child_type:camel State>](node, self)
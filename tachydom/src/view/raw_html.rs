@@ -0,0 +1,191 @@
+use super::{
+    InfallibleRender, Mountable, Position, PositionState, Render, RenderHtml,
+};
+use crate::{
+    html::element::{CreateElement, Div},
+    hydration::Cursor,
+    renderer::{CastFrom, DomRenderer, Renderer},
+};
+
+/// Renders a string as raw HTML, without escaping it.
+///
+/// Text nodes are escaped by default during server rendering. Wrapping a value
+/// in `RawHtml` opts out of this, and inserts it into the document as HTML
+/// instead. This is equivalent to setting `innerHTML`, and should never be
+/// used with untrusted input.
+///
+/// The rendered HTML is followed by a placeholder marker, which is used to
+/// find the end of the raw nodes during hydration. As a result, the raw HTML
+/// itself should not contain comment nodes if it is going to be hydrated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RawHtml<T>(pub T);
+
+/// Creates a view that renders the given string as raw, unescaped HTML.
+#[inline(always)]
+pub fn raw_html<T>(value: T) -> RawHtml<T>
+where
+    T: AsRef<str>,
+{
+    RawHtml(value)
+}
+
+/// View state for [`RawHtml`].
+pub struct RawHtmlState<T, R>
+where
+    R: Renderer,
+{
+    value: T,
+    nodes: Vec<R::Node>,
+    marker: R::Placeholder,
+}
+
+fn parse_html<R>(html: &str) -> Vec<R::Node>
+where
+    R: DomRenderer,
+    Div: CreateElement<R>,
+{
+    let container = R::create_element(Div);
+    R::set_inner_html(&container, html);
+    let mut nodes = Vec::new();
+    let mut next = R::first_child(container.as_ref());
+    while let Some(node) = next {
+        next = R::next_sibling(&node);
+        nodes.push(node);
+    }
+    nodes
+}
+
+impl<T, R> Render<R> for RawHtml<T>
+where
+    T: AsRef<str>,
+    R: DomRenderer,
+    R::Element: Clone,
+    Div: CreateElement<R>,
+{
+    type State = RawHtmlState<T, R>;
+
+    fn build(self) -> Self::State {
+        let nodes = parse_html::<R>(self.0.as_ref());
+        RawHtmlState {
+            value: self.0,
+            nodes,
+            marker: R::create_placeholder(),
+        }
+    }
+
+    fn rebuild(self, state: &mut Self::State) {
+        if self.0.as_ref() == state.value.as_ref() {
+            return;
+        }
+        for node in state.nodes.drain(..) {
+            R::remove(&node);
+        }
+        state.nodes = parse_html::<R>(self.0.as_ref());
+        let parent = R::get_parent(state.marker.as_ref())
+            .and_then(R::Element::cast_from);
+        if let Some(parent) = parent {
+            for node in &state.nodes {
+                R::insert_node(&parent, node, Some(state.marker.as_ref()));
+            }
+        }
+        state.value = self.0;
+    }
+}
+
+impl<T> InfallibleRender for RawHtml<T> {}
+
+impl<T, R> RenderHtml<R> for RawHtml<T>
+where
+    T: AsRef<str>,
+    R: DomRenderer,
+    R::Node: Clone,
+    R::Element: Clone,
+    Div: CreateElement<R>,
+{
+    const MIN_LENGTH: usize = 0;
+
+    fn to_html_with_buf(self, buf: &mut String, position: &mut Position) {
+        // add a comment node to separate from previous sibling, if any
+        if matches!(position, Position::NextChildAfterText) {
+            buf.push_str("<!>")
+        }
+        buf.push_str(self.0.as_ref());
+        // marks the end of the raw nodes
+        buf.push_str("<!>");
+        *position = Position::NextChild;
+    }
+
+    fn to_raw_text_with_buf(self, buf: &mut String) {
+        buf.push_str(self.0.as_ref());
+    }
+
+    fn hydrate<const FROM_SERVER: bool>(
+        self,
+        cursor: &Cursor<R>,
+        position: &PositionState,
+    ) -> Self::State {
+        if position.get() == Position::FirstChild {
+            cursor.child();
+        } else {
+            cursor.sibling();
+        }
+
+        // separating placeholder marker comes before the raw nodes
+        if matches!(position.get(), Position::NextChildAfterText) {
+            cursor.sibling();
+        }
+
+        // every node up to the closing marker was created from the raw HTML
+        let mut nodes = Vec::new();
+        let marker = loop {
+            let node = cursor.current();
+            if let Some(marker) = R::Placeholder::cast_from(node.clone()) {
+                break marker;
+            }
+            let next = R::next_sibling(&node)
+                .expect("couldn't find closing marker for raw HTML");
+            nodes.push(node);
+            cursor.set(next);
+        };
+        position.set(Position::NextChild);
+
+        RawHtmlState {
+            value: self.0,
+            nodes,
+            marker,
+        }
+    }
+}
+
+impl<T, R> Mountable<R> for RawHtmlState<T, R>
+where
+    R: Renderer,
+{
+    fn unmount(&mut self) {
+        for node in &self.nodes {
+            R::remove(node);
+        }
+        self.marker.unmount();
+    }
+
+    fn mount(&mut self, parent: &R::Element, marker: Option<&R::Node>) {
+        for node in &self.nodes {
+            R::insert_node(parent, node, marker);
+        }
+        self.marker.mount(parent, marker);
+    }
+
+    fn insert_before_this(
+        &self,
+        parent: &R::Element,
+        child: &mut dyn Mountable<R>,
+    ) -> bool {
+        match self.nodes.first() {
+            Some(first) => {
+                child.mount(parent, Some(first));
+                true
+            }
+            None => self.marker.insert_before_this(parent, child),
+        }
+    }
+}
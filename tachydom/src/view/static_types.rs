@@ -144,6 +144,10 @@ where
         *position = Position::NextChildAfterText;
     }
 
+    fn to_raw_text_with_buf(self, buf: &mut String) {
+        buf.push_str(V);
+    }
+
     fn hydrate<const FROM_SERVER: bool>(
         self,
         cursor: &Cursor<R>,
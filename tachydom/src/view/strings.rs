@@ -2,11 +2,43 @@ use super::{
     InfallibleRender, Mountable, Position, PositionState, Render, RenderHtml,
     ToTemplate,
 };
-use crate::{
-    hydration::Cursor,
-    renderer::{CastFrom, Renderer},
-};
-use std::{rc::Rc, sync::Arc};
+use crate::{hydration::Cursor, renderer::Renderer};
+use std::{borrow::Cow, fmt, rc::Rc, sync::Arc};
+
+/// Escapes a string so that it can be safely included as the text content of
+/// an HTML element.
+///
+/// Only `&`, `<`, and `>` need to be escaped in text content; quotes are left
+/// as they are. (See [`escape_attr`](crate::html::attribute::escape_attr) for
+/// attribute values.)
+pub fn escape_text(value: &str) -> Cow<'_, str> {
+    let Some(first) = value.find(['&', '<', '>']) else {
+        return Cow::Borrowed(value);
+    };
+    let mut escaped = String::with_capacity(value.len() + 8);
+    escaped.push_str(&value[..first]);
+    for ch in value[first..].chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(ch),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// A [`fmt::Write`] adapter that escapes everything written to it as HTML
+/// text, so that [`Display`](fmt::Display) types can be written directly into
+/// an HTML buffer.
+pub(crate) struct EscapedText<'a>(pub &'a mut String);
+
+impl fmt::Write for EscapedText<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.push_str(&escape_text(s));
+        Ok(())
+    }
+}
 
 pub struct StrState<'a, R: Renderer> {
     pub node: R::Text,
@@ -45,10 +77,14 @@ where
         if matches!(position, Position::NextChildAfterText) {
             buf.push_str("<!>")
         }
-        buf.push_str(self);
+        buf.push_str(&escape_text(self));
         *position = Position::NextChildAfterText;
     }
 
+    fn to_raw_text_with_buf(self, buf: &mut String) {
+        buf.push_str(self);
+    }
+
     fn hydrate<const FROM_SERVER: bool>(
         self,
         cursor: &Cursor<R>,
@@ -65,9 +101,7 @@ where
             cursor.sibling();
         }
 
-        let node = cursor.current();
-        let node = R::Text::cast_from(node)
-            .expect("couldn't cast text node from node");
+        let node = cursor.expect_text::<FROM_SERVER>(self);
 
         if !FROM_SERVER {
             R::set_text(&node, self);
@@ -157,6 +191,10 @@ where
         <&str as RenderHtml<R>>::to_html_with_buf(self.as_str(), buf, position)
     }
 
+    fn to_raw_text_with_buf(self, buf: &mut String) {
+        buf.push_str(&self);
+    }
+
     fn hydrate<const FROM_SERVER: bool>(
         self,
         cursor: &Cursor<R>,
@@ -243,6 +281,10 @@ where
         <&str as RenderHtml<R>>::to_html_with_buf(&self, buf, position)
     }
 
+    fn to_raw_text_with_buf(self, buf: &mut String) {
+        buf.push_str(&self);
+    }
+
     fn hydrate<const FROM_SERVER: bool>(
         self,
         cursor: &Cursor<R>,
@@ -330,6 +372,10 @@ where
         <&str as RenderHtml<R>>::to_html_with_buf(&self, buf, position)
     }
 
+    fn to_raw_text_with_buf(self, buf: &mut String) {
+        buf.push_str(&self);
+    }
+
     fn hydrate<const FROM_SERVER: bool>(
         self,
         cursor: &Cursor<R>,
@@ -380,3 +426,120 @@ impl<R: Renderer> Mountable<R> for ArcStrState<R> {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::escape_text;
+    use crate::{
+        html::{
+            attribute::global::GlobalAttributes,
+            element::{div, p, script, style, ElementChild, HtmlElement, P},
+        },
+        renderer::{mock_dom::MockDom, Renderer},
+        view::{raw_html::raw_html, Render, RenderHtml},
+    };
+
+    #[test]
+    fn escape_text_only_allocates_when_necessary() {
+        assert!(matches!(
+            escape_text("plain"),
+            std::borrow::Cow::Borrowed(_)
+        ));
+        assert_eq!(
+            escape_text("<b>\"Tom\" & 'Jerry'</b>"),
+            "&lt;b&gt;\"Tom\" &amp; 'Jerry'&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn text_is_escaped_in_html() {
+        let el: HtmlElement<_, _, _, MockDom> = div().child((
+            "<script>alert('hi')</script>",
+            String::from("a & b"),
+            '<',
+        ));
+        assert_eq!(
+            el.to_html(),
+            "<div>&lt;script&gt;alert('hi')&lt;/script&gt;<!>a &amp; \
+             b<!>&lt;</div>"
+        );
+    }
+
+    #[test]
+    fn raw_text_is_not_escaped_in_html() {
+        let el: HtmlElement<_, _, _, MockDom> =
+            script().child("if (a < b && b > c) { f(() => 1) }");
+        assert_eq!(
+            el.to_html(),
+            "<script>if (a < b && b > c) { f(() => 1) }</script>"
+        );
+        let el: HtmlElement<_, _, _, MockDom> =
+            style().child(String::from("p > a { content: \"&lt;\" }"));
+        assert_eq!(el.to_html(), "<style>p > a { content: \"&lt;\" }</style>");
+    }
+
+    #[test]
+    fn raw_text_children_have_no_separators() {
+        let el: HtmlElement<_, _, _, MockDom> =
+            script().child(("let a = ", 1, "; let b = a > 0;"));
+        assert_eq!(el.to_html(), "<script>let a = 1; let b = a > 0;</script>");
+        let el: HtmlElement<_, _, _, MockDom> =
+            script().child(raw_html("let a = \"&amp;\";"));
+        assert_eq!(el.to_html(), "<script>let a = \"&amp;\";</script>");
+    }
+
+    #[test]
+    fn raw_text_cannot_close_its_element() {
+        let el: HtmlElement<_, _, _, MockDom> =
+            script().child("let a = \"</script><script>alert(1)</SCRIPT \";");
+        assert_eq!(
+            el.to_html(),
+            "<script>let a = \"<\\/script><script>alert(1)<\\/SCRIPT \";</script>"
+        );
+        let el: HtmlElement<_, _, _, MockDom> =
+            style().child(("a::after { content: \"</", "Style>\" }"));
+        assert_eq!(
+            el.to_html(),
+            "<style>a::after { content: \"<\\/Style>\" }</style>"
+        );
+    }
+
+    #[test]
+    fn attributes_are_escaped_in_html() {
+        let el: HtmlElement<_, _, _, MockDom> =
+            div().id("\"><script>").title("a & b");
+        assert_eq!(
+            el.to_html(),
+            "<div id=\"&quot;&gt;&lt;script&gt;\" title=\"a &amp; \
+             b\"></div>"
+        );
+    }
+
+    #[test]
+    fn raw_html_is_not_escaped() {
+        let el: HtmlElement<_, _, _, MockDom> =
+            div().child(("<", raw_html("<b>bold</b>")));
+        assert_eq!(el.to_html(), "<div>&lt;<!><b>bold</b><!></div>");
+    }
+
+    #[test]
+    fn escaped_text_hydrates() {
+        let view =
+            || -> HtmlElement<P, _, _, MockDom> { p().child("1 < 2 && 3 > 2") };
+        assert_eq!(view().to_html(), "<p>1 &lt; 2 &amp;&amp; 3 &gt; 2</p>");
+
+        // the browser unescapes entities when it parses the server HTML
+        let root = MockDom::create_element(crate::html::element::Div);
+        let el = MockDom::create_element(crate::html::element::P);
+        let text = MockDom::create_text_node("1 < 2 && 3 > 2");
+        MockDom::insert_node(&root, el.as_ref(), None);
+        MockDom::insert_node(&el, text.as_ref(), None);
+
+        let mut state = view().hydrate_from::<true>(&root);
+        assert_eq!(state.el, el);
+        assert_eq!(root.to_debug_html(), "<div><p>1 < 2 && 3 > 2</p></div>");
+
+        p().child("4 > 3").rebuild(&mut state);
+        assert_eq!(root.to_debug_html(), "<div><p>4 > 3</p></div>");
+    }
+}
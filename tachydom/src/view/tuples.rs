@@ -3,6 +3,7 @@ use super::{
     ToTemplate,
 };
 use crate::{
+    html::{attribute::Attribute, element::AddAnyAttr},
     hydration::Cursor,
     view::{FallibleRender, InfallibleRender, StreamBuilder},
 };
@@ -31,6 +32,8 @@ where
 
     fn to_html_with_buf(self, _buf: &mut String, _position: &mut Position) {}
 
+    fn to_raw_text_with_buf(self, _buf: &mut String) {}
+
     fn hydrate<const FROM_SERVER: bool>(
         self,
         _cursor: &Cursor<R>,
@@ -117,6 +120,10 @@ where
         self.0.to_html_async_with_buf::<OUT_OF_ORDER>(buf, position);
     }
 
+    fn to_raw_text_with_buf(self, buf: &mut String) {
+        self.0.to_raw_text_with_buf(buf);
+    }
+
     fn hydrate<const FROM_SERVER: bool>(
         self,
         cursor: &Cursor<R>,
@@ -126,6 +133,23 @@ where
     }
 }
 
+impl<A, R> AddAnyAttr<R> for (A,)
+where
+    A: AddAnyAttr<R>,
+    R: Renderer,
+    R::Node: Clone,
+    R::Element: Clone,
+{
+    type Output<SomeNewAttr: Attribute<R>> = (A::Output<SomeNewAttr>,);
+
+    fn add_any_attr<NewAttr: Attribute<R>>(
+        self,
+        attr: NewAttr,
+    ) -> Self::Output<NewAttr> {
+        (self.0.add_any_attr(attr),)
+    }
+}
+
 impl<A: ToTemplate> ToTemplate for (A,) {
     const TEMPLATE: &'static str = A::TEMPLATE;
     const CLASS: &'static str = A::CLASS;
@@ -236,6 +260,14 @@ macro_rules! impl_view_for_tuples {
 				}
 			}
 
+			fn to_raw_text_with_buf(self, buf: &mut String) {
+				paste::paste! {
+					let ([<$first:lower>], $([<$ty:lower>],)* ) = self;
+					[<$first:lower>].to_raw_text_with_buf(buf);
+					$([<$ty:lower>].to_raw_text_with_buf(buf));*
+				}
+			}
+
 			fn hydrate<const FROM_SERVER: bool>(self, cursor: &Cursor<Rndr>, position: &PositionState) -> Self::State {
 				paste::paste! {
 					let ([<$first:lower>], $([<$ty:lower>],)* ) = self;
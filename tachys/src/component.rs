@@ -81,3 +81,23 @@ where
         (self)(props)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use tachydom::view::RenderHtml;
+
+    #[component]
+    fn Button(label: &'static str) -> impl RenderHtml<Dom> + AddAnyAttr<Dom> {
+        view! { <button class="btn">{label}</button> }
+    }
+
+    #[test]
+    fn attributes_are_forwarded_to_single_element_components() {
+        let view = view! { <Button label="Go" attr:id="go" class:big=true/> };
+        assert_eq!(
+            view.to_html(),
+            "<button id=\"go\" class=\"btn big\">Go</button>"
+        );
+    }
+}
@@ -6,20 +6,69 @@
 			c();
 		}
 	}
+	function visible(el, c) {
+		if (!("IntersectionObserver" in window)) return c();
+		const observer = new IntersectionObserver(entries => {
+			if (entries.some(entry => entry.isIntersecting)) {
+				observer.disconnect();
+				c();
+			}
+		});
+		observer.observe(el);
+	}
+	function interaction(el, c) {
+		const events = ["pointerover", "pointerdown", "touchstart", "focusin", "keydown"];
+		function start() {
+			for (const ev of events) el.removeEventListener(ev, start, true);
+			c();
+		}
+		for (const ev of events) el.addEventListener(ev, start, { capture: true, passive: true });
+	}
+	function media(query, c) {
+		const mql = window.matchMedia(query);
+		if (mql.matches) return c();
+		mql.addEventListener("change", function change() {
+			if (mql.matches) {
+				mql.removeEventListener("change", change);
+				c();
+			}
+		});
+	}
+	// waits for the strategy in the island's data-hydrate attribute
+	function schedule(el, c) {
+		switch (el.dataset.hydrate) {
+			case "idle": return idle(c);
+			case "visible": return visible(el, c);
+			case "interaction": return interaction(el, c);
+			case "media": return media(el.dataset.media, c);
+			default: return c();
+		}
+	}
 	idle(() => {
 		import(`/${pkg_path}/${output_name}.js`)
 			.then(mod => {
 				mod.default(`/${pkg_path}/${wasm_output_name}.wasm`).then(() => {
 					mod.hydrate();
+					// resolves once each island has been hydrated
+					const hydrated = new Map();
 					for (let e of document.querySelectorAll("leptos-island")) {
 						const l = e.dataset.component;
 						const islandFn = mod["_island_" + l];
-						if (islandFn) {
-							console.log("running islandFn on ", e);
-							islandFn(e);
-						} else {
-							console.warn(`Could not find WASM function for the island ${l}.`);
-						}
+						// nested islands wait for the closest island around them,
+						// so that they can use the context it provides
+						const outer = e.parentElement && e.parentElement.closest("leptos-island");
+						const ready = (outer && hydrated.get(outer)) || Promise.resolve();
+						hydrated.set(e, ready.then(() => new Promise(resolve => {
+							if (islandFn) {
+								schedule(e, () => {
+									islandFn(e);
+									resolve();
+								});
+							} else {
+								console.warn(`Could not find WASM function for the island ${l}.`);
+								resolve();
+							}
+						})));
 					}
 				});
 			})
@@ -20,9 +20,10 @@ pub fn AutoReload<'a>(
             leptos_config::ReloadWSProtocol::WSS => "'wss://'",
         };
 
+        let nonce = nonce.map(Nonce::from).or_else(use_nonce);
         let script = include_str!("reload_script.js");
         view! {
-            <script crossorigin=nonce>
+            <script nonce=nonce>
                 {format!("{script}({reload_port:?}, {protocol})")}
             </script>
         }
@@ -40,7 +41,7 @@ pub fn HydrationScripts(
     if std::option_env!("LEPTOS_OUTPUT_NAME").is_none() {
         wasm_output_name.push_str("_bg");
     }
-    let nonce = None::<String>; // use_nonce(); // TODO
+    let nonce = use_nonce();
     let script = if islands {
         include_str!("./island_script.js")
     } else {
@@ -49,9 +50,25 @@ pub fn HydrationScripts(
 
     view! {
         <link rel="modulepreload" href=format!("/{pkg_path}/{output_name}.js") nonce=nonce.clone()/>
-        <link rel="preload" href=format!("/{pkg_path}/{wasm_output_name}.wasm") r#as="fetch" r#type="application/wasm" crossorigin=nonce.clone()/>
+        <link rel="preload" href=format!("/{pkg_path}/{wasm_output_name}.wasm") r#as="fetch" r#type="application/wasm" crossorigin="" nonce=nonce.clone()/>
         <script type="module" nonce=nonce>
             {format!("{script}({pkg_path:?}, {output_name:?}, {wasm_output_name:?})")}
         </script>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::HydrationScripts;
+    use leptos_config::LeptosOptions;
+    use tachy_maccy::view;
+    use tachydom::view::RenderHtml;
+
+    #[test]
+    fn hydration_script_is_not_escaped() {
+        let options = LeptosOptions::default();
+        let html = view! { <HydrationScripts options/> }.to_html();
+        assert!(html.contains("=>"));
+        assert!(!html.contains("&gt;"));
+    }
+}
@@ -1,5 +1,5 @@
 pub mod prelude {
-    pub use tachy_maccy::{component, view};
+    pub use tachy_maccy::{component, slot, view};
     pub use tachy_reaccy::prelude::*;
     pub use tachydom::prelude::*;
 }
@@ -11,9 +11,13 @@ pub mod component;
 mod for_loop;
 mod hydration_scripts;
 mod show;
+mod suspense;
 pub use for_loop::*;
 pub use hydration_scripts::*;
+#[doc(hidden)]
+pub use serde;
 pub use show::*;
+pub use suspense::*;
 pub use tachy_maccy::*;
 pub use tachy_reaccy;
 pub use tachydom;
@@ -0,0 +1,19 @@
+use crate::children::{Children, ViewFn};
+use tachy_maccy::component;
+use tachydom::{async_views::suspense, renderer::dom::Dom, view::RenderHtml};
+
+/// Shows a single fallback while any of the async work in its children, like
+/// suspended futures and resources being awaited, is still loading.
+///
+/// Once everything is ready, all of the children are shown at once. When
+/// streaming out of order, they are sent as one fragment.
+#[component]
+pub fn Suspense(
+    /// The view that is shown while the children are loading. By default this is the empty view.
+    #[prop(optional, into)]
+    fallback: ViewFn,
+    /// The children, which are created inside the boundary.
+    children: Children,
+) -> impl RenderHtml<Dom> {
+    suspense(fallback.run(), children)
+}
@@ -0,0 +1,11 @@
+use tachys::prelude::*;
+
+#[component]
+fn Labels() -> impl RenderHtml<Dom> + AddAnyAttr<Dom> {
+    view! {
+        <label>"First"</label>
+        <label>"Second"</label>
+    }
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+#[tachys::island]
+fn Counter() -> impl RenderHtml<Dom> + AddAnyAttr<Dom> {
+    view! { <button>"+1"</button> }
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use tachys::prelude::*;
+
+#[component]
+fn Button() -> impl RenderHtml<Dom> {
+    view! { <button>"Go"</button> }
+}
+
+fn main() {
+    let _ = view! { <Button attr:id="go"/> };
+}